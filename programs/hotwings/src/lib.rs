@@ -1,613 +1,4402 @@
-use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Transfer}; 
-
-declare_id!("6vxBssG3FvWset4jv3STQGGnq3mTqkkD2BSbYC5s7j89");
-
-
-#[program]
-pub mod hotwings {
-    use anchor_spl::token;
-
-    use super::*;
-
-    pub fn initialize_lock_accounts(
-        ctx: Context<InitializeLockAccounts>,
-        users: Vec<InvestorInfo>, // Batch of users
-    ) -> Result<()> {
-        let lock_pool = &mut ctx.accounts.lock_pool_account;
-
-        // ✅ Security: Check if pool is already initialized
-        require!(
-            ctx.accounts.lock_pool_token_account.owner == ctx.accounts.pda.key(),
-            CustomError::Unauthorized
-        );
-        require!(
-            lock_pool.start_time == 0,
-            CustomError::AlreadyInitialized
-        );
-
-         // Step 1: Initialize start_time if not already set
-        if lock_pool.start_time == 0 {
-            // Fetch current cluster time
-            let clock = Clock::get()?; // Gets the current clock (cluster time)
-            lock_pool.start_time = clock.unix_timestamp; // Set `start_time` using Solana clock
-        }
-
-        for user in users.iter() {
-            // ✅ Security Check: Ensure token amount is valid
-            require!(user.token_amount > 0, CustomError::InvalidTokenAmount);
-
-            // Step 1: Transfer tokens to the shared lock pool token account
-            let cpi_accounts = Transfer {
-                from: ctx.accounts.source_wallet.to_account_info(), // Admin's source wallet
-                to: ctx.accounts.lock_pool_token_account.to_account_info(), // Centralized lock pool
-                authority: ctx.accounts.admin_wallet.to_account_info(), // Admin wallet signature
-            };
-            let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-            token::transfer(cpi_ctx, user.token_amount)?;
-
-            // Step 2: Add user locking information to LockPoolState
-            let new_user_lock_info = UserLockInfo {
-                user_wallet: user.wallet_address,
-                total_tokens: user.token_amount,
-                unlocked_tokens: 0, // Start with 0 unlocked tokens
-                locked_tokens: user.token_amount,
-            };
-
-            // Push the user into the lock pool state and update total locked
-            lock_pool.users.push(new_user_lock_info);
-            lock_pool.total_locked += user.token_amount;
-        }
-    
-        Ok(())
-    }
-
-    pub fn unlock_tokens(ctx: Context<UnlockTokens>, market_cap: u64) -> Result<()> {
-        let lock_pool = &mut ctx.accounts.lock_pool_account;
-        // ✅ Security Check: Ensure caller is admin
-        require!(
-            ctx.accounts.admin_wallet.key() == ctx.accounts.pda.key(),
-            CustomError::Unauthorized
-        );
-
-        // Ensure the admin wallet is authorized
-        let admin = &ctx.accounts.admin_wallet;
-        require!(admin.is_signer, CustomError::Unauthorized); // Check if the admin is the signer
-    
-        // Fetch current milestone percentage
-        let percentage = milestone_percentage(market_cap);
-        let current_milestone = lock_pool.current_milestone;
-    
-        // Ensure we don’t process the same milestone multiple times
-        require!(percentage > current_milestone * 10, CustomError::MilestoneNotReached);
-
-        let percentage = milestone_percentage(market_cap);
-        require!(percentage > 0, CustomError::MilestoneNotReached);
-        
-        for user in lock_pool.users.iter_mut() {
-            let total_to_unlock = user.total_tokens * percentage as u64 / 100;
-            let newly_unlocked = total_to_unlock - user.unlocked_tokens;
-    
-            user.unlocked_tokens = total_to_unlock;    // Update unlocked tokens state
-            user.locked_tokens -= newly_unlocked;     // Reduce locked tokens
-    
-            // Transfer unlocked tokens to the user
-            let cpi_accounts = Transfer {
-                from: ctx.accounts.lock_pool_token_account.to_account_info(),
-                to: user.user_wallet.to_account_info(),
-                authority: ctx.accounts.pda.to_account_info(),
-            };
-            let cpi_program = ctx.accounts.token_program.to_account_info();
-            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-            token::transfer(cpi_ctx, newly_unlocked)?;
-        }
-    
-        // Update current milestone
-        lock_pool.current_milestone = (percentage / 10) as u8;
-    
-        Ok(())
-    }
-
-    pub fn full_unlock(ctx: Context<FullUnlock>) -> Result<()> {
-        let lock_pool = &mut ctx.accounts.lock_pool_account;
-    
-        // Ensure that the full unlock has not been executed yet
-        require!(!lock_pool.full_unlock_executed, CustomError::FullUnlockAlreadyExecuted);
-    
-        // Get the current Solana cluster time
-        let current_time = ctx.accounts.clock.unix_timestamp;
-    
-        // Ensure that 3 months have passed since `start_time`
-        require!(
-            current_time >= lock_pool.start_time + (3 * 30 * 24 * 60 * 60), // 3 months in seconds
-            CustomError::UnlockTooSoon
-        );
-    
-        // Iterate over all users to unlock their remaining locked tokens
-        for user in lock_pool.users.iter_mut() {
-            let newly_unlocked_tokens = user.locked_tokens; // All remaining locked tokens
-    
-            // Check to avoid unnecessary processing
-            if newly_unlocked_tokens > 0 {
-                // Update user's token state
-                user.unlocked_tokens += newly_unlocked_tokens;
-                user.locked_tokens = 0;
-    
-                // Transfer all remaining locked tokens from lock pool account to the user's wallet
-                let cpi_accounts = Transfer {
-                    from: ctx.accounts.lock_pool_token_account.to_account_info(),
-                    to: user.user_wallet.to_account_info(),
-                    authority: ctx.accounts.pda.to_account_info(),
-                };
-                let cpi_program = ctx.accounts.token_program.to_account_info();
-                let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    
-                token::transfer(cpi_ctx, newly_unlocked_tokens)?;
-            }
-        }
-    
-        // Mark full unlock as executed
-        lock_pool.full_unlock_executed = true;
-    
-        Ok(())
-    }
-
-    pub fn purchase_tokens(ctx: Context<PurchaseTokens>, total_paid_tokens: u64) -> Result<()> {
-        let lock_pool = &mut ctx.accounts.lock_pool_account;
-    
-        // Error 1: Ensure `total_paid_tokens` is greater than 0
-        require!(total_paid_tokens > 0, CustomError::InvalidTokenAmount);
-        // Determine the percentage of tokens to unlock immediately based on the current milestone
-        let unlock_percentage = milestone_percentage_from_milestone(lock_pool.current_milestone);
-    
-        // Calculate unlocked and locked tokens
-        let unlocked_tokens = total_paid_tokens * unlock_percentage as u64 / 100;
-        let locked_tokens = total_paid_tokens - unlocked_tokens;
-
-        // Error 2: Ensure the lock pool has enough tokens for the unlocked portion
-        require!(
-            ctx.accounts.lock_pool_token_account.amount >= unlocked_tokens,
-            CustomError::InsufficientPoolBalance
-        );
-    
-        // Handle unlocked tokens: Transfer `unlocked_tokens` directly to the user's wallet
-        if unlocked_tokens > 0 {
-            let cpi_accounts = Transfer {
-                from: ctx.accounts.lock_pool_token_account.to_account_info(),
-                to: ctx.accounts.user_token_account.to_account_info(),
-                authority: ctx.accounts.pda.to_account_info(),
-            };
-            let cpi_program = ctx.accounts.token_program.to_account_info();
-            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    
-            token::transfer(cpi_ctx, unlocked_tokens)?;
-        }
-    
-        
-    
-        // Handle locked tokens: Add locked tokens to the LockPoolState for this user
-        if locked_tokens > 0 {
-            // Step: Transfer tokens to the shared lock pool token account
-            let cpi_accounts_lock_transfer = Transfer {
-                from: ctx.accounts.token_pool_account.to_account_info(), // Source is Raydium token pool
-                to: ctx.accounts.lock_pool_token_account.to_account_info(), // Destination is Lock Pool Token Account
-                authority: ctx.accounts.pda.to_account_info(), // Authority is program-derived
-            };
-            let lock_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts_lock_transfer);
-            token::transfer(lock_ctx, locked_tokens)?;
-
-            // Check if the user is already in the LockPoolState
-            if let Some(user) = lock_pool.users.iter_mut().find(|u| u.user_wallet == ctx.accounts.user_wallet.key()) {
-                // Update existing user's locked tokens
-                user.total_tokens += locked_tokens;
-                user.locked_tokens += locked_tokens;
-            } else {
-                // Add new user entry to the LockPool
-                lock_pool.users.push(UserLockInfo {
-                    user_wallet: ctx.accounts.user_wallet.key(), // Buyer’s wallet
-                    total_tokens: locked_tokens,                 // Total tokens purchased
-                    locked_tokens,                              // Tokens still locked
-                    unlocked_tokens: 0,                         // Tokens immediately unlocked
-                });
-            }
-        }
-    
-        Ok(())
-    }
-
-    pub fn finalize_unlock(ctx: Context<FinalizeUnlock>) -> Result<()> {
-        let lock_pool = &mut ctx.accounts.lock_pool_account;
-        
-        let clock = Clock::get()?; // Get Solana cluster time
-        let current_time = clock.unix_timestamp;
-    
-        // Ensure unlock conditions are met: either final milestone or 3-month full unlock
-        let unlock_condition_met = lock_pool.current_milestone >= 8
-            || current_time >= lock_pool.start_time + (3 * 30 * 24 * 60 * 60); // 3 months
-        require!(unlock_condition_met, CustomError::UnlockTooSoon);
-    
-        // Calculate 25% auto-sell amount
-        let auto_sell_tokens = ctx
-            .accounts
-            .project_wallet
-            .amount
-            .checked_mul(25)
-            .and_then(|v| v.checked_div(100))
-            .ok_or(CustomError::InvalidTokenAmount)?;
-    
-        require!(auto_sell_tokens > 0, CustomError::InvalidTokenAmount);
-    
-        // Perform token transfer for auto-sell
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.project_wallet.to_account_info(),
-            to: ctx.accounts.dex_liquidity_wallet.to_account_info(),
-            authority: ctx.accounts.project_wallet_authority.to_account_info(),
-        };
-        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-        token::transfer(cpi_ctx, auto_sell_tokens)?;
-    
-        // Deactivate maximum hold limit
-        lock_pool.is_max_hold_limit_active = false;
-    
-        Ok(())
-    }
-
-
-}
-
-// =======================================================struct=============================================
-
-#[derive(Accounts)]
-pub struct TransferHookContext<'info> {
-    #[account(mut)]
-    pub source_wallet: Account<'info, TokenAccount>, // Source of the transfer
-    #[account(mut)]
-    pub destination_wallet: Account<'info, TokenAccount>, // Destination of the transfer
-    #[account(mut)]
-    pub burn_wallet: Account<'info, TokenAccount>, // Burn Wallet
-    #[account(mut)]
-    pub marketing_wallet: Account<'info, TokenAccount>, // Marketing Wallet
-    /// CHECK: The transfer instruction (modifies amount)
-    pub transfer_instruction: AccountInfo<'info>,
-    pub authority: AccountInfo<'info>, // PDA for Token Authority
-    pub token_program: Program<'info, Token>, // SPL Token program
-    #[account(mut)]
-    pub lock_pool_account: Account<'info, LockPoolState>, // Track all locking data for users
-    #[account(mut)]
-    pub lock_pool_token_account: Account<'info, TokenAccount>, // Shared lock vault (PDA-owned)
-    pub user_wallet: Signer<'info>, // User wallet
-}
-
-#[derive(Accounts)]
-pub struct InitializeLockAccounts<'info> {
-    #[account(mut)]
-    pub lock_pool_account: Account<'info, LockPoolState>, // Track all locking data for users
-    #[account(mut)]
-    pub lock_pool_token_account: Account<'info, TokenAccount>, // Shared lock vault (PDA-owned)
-    #[account(mut)]
-    pub source_wallet: Account<'info, TokenAccount>, // Admin's funding source wallet
-    #[account(mut)]
-    pub admin_wallet: Signer<'info>, // Wallet signing token transfers (Presale Manager)
-    pub token_program: Program<'info, Token>, // Standard SPL Token program
-    pub clock: Sysvar<'info, Clock>, // Fetch cluster time from SysvarClock
-}
-
-#[derive(Clone, AnchorSerialize, AnchorDeserialize, Debug)]
-pub struct InvestorInfo {
-    pub wallet_address: Pubkey,
-    pub token_amount: u64,
-}
-
-#[account]
-pub struct LockPoolState {
-    pub total_locked: u64,               // Total locked tokens in the pool
-    pub users: Vec<UserLockInfo>,       // List of all users and locked info
-    pub start_time: i64,  
-    pub current_milestone: u8, 
-    pub full_unlock_executed: bool
-    pub is_max_hold_limit_active: bool,  // NEW: Enable/Disable max hold restrictions
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct UserLockInfo {
-    pub user_wallet: Pubkey,            // Wallet address of the user
-    pub total_tokens: u64,              // Purchased tokens during presale
-    pub unlocked_tokens: u64,           // Unlocked tokens (via milestones)
-    pub locked_tokens: u64,             // Remaining locked tokens
-}
-
-#[derive(Accounts)]
-pub struct UnlockTokens<'info> {
-    #[account(mut)]
-    pub lock_pool_account: Account<'info, LockPoolState>, // Global LockPoolState (tracks locking state across users)
-    #[account(mut)]
-    pub lock_pool_token_account: Account<'info, TokenAccount>, // PDA-controlled SPL token account (the lock pool)
-    /// CHECK: The PDA account, which acts as the authority for the LockPoolTokenAccount.
-    pub pda: AccountInfo<'info>, // Program Derived Address (authority of LockPoolTokenAccount)
-    #[account(mut)]
-    pub admin_wallet: Signer<'info>, // ADMIN WALLET to trigger the unlocking process
-    pub token_program: Program<'info, Token>, // SPL Token program for token transfers
-}
-
-#[derive(Accounts)]
-pub struct FullUnlock<'info> {
-    #[account(mut)]
-    pub lock_pool_account: Account<'info, LockPoolState>, // Global LockPoolState (tracks locking state across users)
-    #[account(mut)]
-    pub lock_pool_token_account: Account<'info, TokenAccount>, // PDA-controlled SPL token account (the lock pool)
-    /// CHECK: PDA authority over the LockPool Token Account
-    pub pda: AccountInfo<'info>, // Program Derived Address (authority of LockPoolTokenAccount)
-    #[account(mut)]
-    pub admin_wallet: Signer<'info>, // ADMIN WALLET to trigger the full unlock operation
-    pub token_program: Program<'info, Token>, // SPL Token program for token transfers
-    pub clock: Sysvar<'info, Clock>, // Solana Clock Sysvar to fetch current cluster time
-}
-
-#[derive(Accounts)]
-pub struct PurchaseTokens<'info> {
-    #[account(mut)]
-    pub lock_pool_account: Account<'info, LockPoolState>, // Global LockPoolState
-    #[account(mut)]
-    pub lock_pool_token_account: Account<'info, TokenAccount>, // PDA-controlled lock pool account
-    /// CHECK: Program Derived Address (PDA) for authority over the LockPool
-    pub pda: AccountInfo<'info>, // PDA authority for lock pool transfers
-    #[account(mut)]
-    pub user_wallet: Signer<'info>, // Buyer's wallet (receiving unlocked tokens)
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>, // Buyer's token account to receive unlocked tokens
-    pub token_program: Program<'info, Token>, // SPL Token Program
-}
-
-#[derive(Accounts)]
-pub struct FinalizeUnlock<'info> {
-    #[account(mut)]
-    pub lock_pool_account: Account<'info, LockPoolState>, // Global LockPoolState (tracks locking state across users)
-    /// CHECK: Project wallet (source of auto-sales)
-    #[account(mut)]
-    pub project_wallet: Account<'info, TokenAccount>, // Project/Presale Manager's wallet
-    /// CHECK: Authority over the `project_wallet`
-    pub project_wallet_authority: Signer<'info>, // Authority to approve sales from the project wallet
-    #[account(mut)]
-    pub dex_liquidity_wallet: Account<'info, TokenAccount>, // Wallet or DEX account receiving the auto-sell tokens
-    pub token_program: Program<'info, Token>, // SPL Token program for transfers
-    pub clock: Sysvar<'info, Clock>, // Solana Clock Sysvar to fetch current cluster time
-}
-
-
-fn milestone_percentage(market_cap: u64) -> u8 {
-    if market_cap >= 2_500_000 {
-        return 100; // If the market cap exceeds or equals the last milestone, unlock 100%
-    } else if market_cap >= 1_574_000 {
-        return 70; // Milestone 7
-    } else if market_cap >= 997_000 {
-        return 60; // Milestone 6
-    } else if market_cap >= 650_000 {
-        return 50; // Milestone 5
-    } else if market_cap >= 395_000 {
-        return 40; // Milestone 4
-    } else if market_cap >= 225_000 {
-        return 30; // Milestone 3
-    } else if market_cap >= 105_500 {
-        return 20; // Milestone 2
-    } else if market_cap >= 45_000 {
-        return 10; // Milestone 1
-    }
-    0 // If market cap is below the first milestone, no tokens are unlocked
-}
-
-fn milestone_percentage_from_milestone(current_milestone: u8) -> u8 {
-    match current_milestone {
-        1 => 10, // Milestone 1: 10%
-        2 => 20, // Milestone 2: 20%
-        3 => 30, // Milestone 3: 30%
-        4 => 40, // Milestone 4: 40%
-        5 => 50, // Milestone 5: 50%
-        6 => 60, // Milestone 6: 60%
-        7 => 70, // Milestone 7: 70%
-        8 => 100, // Milestone 8: 100%
-        _ => 0,   // Fallback: No unlock for unknown milestones
-    }
-}
-
-// =============================================TransferHook================================================
-
-fn initialize_token_with_transfer_hook(
-    payer: Pubkey,
-    mint_authority: Pubkey,
-    freeze_authority: Option<Pubkey>,
-) -> Result<()> {
-    let mint = Keypair::new(); // Generate a keypair for the mint
-
-    // Specify the extensions to enable
-    let extensions = vec![
-        ExtensionType::TransferHook, // Enables the TransferHook logic
-    ];
-
-    // Create the mint with the above extensions
-    let instruction = initialize_mint_with_extension(
-        &spl_token_2022::id(),
-        &mint.pubkey(),
-        &payer,
-        &mint_authority,
-        freeze_authority.as_ref(),
-        extensions, // TransferHook extension
-    );
-
-    // Send the transaction to initialize the mint
-    let tx = Transaction::new_signed_with_payer(
-        &[instruction],
-        Some(&payer),
-        &[payer_signer],
-        recent_blockhash,
-    );
-    banks_client.process_transaction(tx)?;
-
-    Ok(())
-}
-
-pub fn process_transfer_hook(ctx: Context<TransferHookContext>) -> Result<()> {
-    // Extract the program IDs for source and destination
-    let source_program_id = ctx.accounts.source_wallet.owner(); // SPL Token account owning program ID
-    let destination_program_id = ctx.accounts.destination_wallet.owner(); // SPL Token account owning program ID
-
-    // Check if it's a DEX transaction
-    let is_dex = is_dex_transaction(source_program_id, destination_program_id);
-
-    if is_dex {
-        // Tax Logic
-        let transfer_amount = ctx.accounts.transfer_instruction.amount;
-        let tax = transfer_amount * 15 / 1000; // 1.5% total tax
-        let burn_amount = tax / 2; // 0.75% for Burn
-        let marketing_amount = tax / 2; // 0.75% for Marketing Wallet
-        let net_transfer = transfer_amount - tax;
-        let lock_pool = &mut ctx.accounts.lock_pool_account;
-
-        if lock_pool.is_max_hold_limit_active {
-            let user_hold_amount = lock_pool.users.iter()
-                .find(|u| u.user_wallet == ctx.accounts.user_wallet.key())
-                .map_or(0, |u| u.total_tokens + net_transfer);
-        
-            require!(
-                user_hold_amount <= MAX_HOLD_AMOUNT,
-                CustomError::MaxHoldExceeded
-            );
-        }
-    
-        // Burn tokens
-        let cpi_ctx_burn = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.source_wallet.to_account_info(),
-                to: ctx.accounts.burn_wallet.to_account_info(),
-                authority: ctx.accounts.authority.clone(),
-            },
-            ctx.signer_seeds, // PDA signer
-        );
-        token::transfer(cpi_ctx_burn, burn_amount)?;
-
-        // Send tokens to marketing wallet
-        let cpi_ctx_marketing = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.source_wallet.to_account_info(),
-                to: ctx.accounts.marketing_wallet.to_account_info(),
-                authority: ctx.accounts.authority.clone(),
-            },
-            ctx.signer_seeds, // PDA signer
-        );
-        token::transfer(cpi_ctx_marketing, marketing_amount)?;
-
-        // Update the final amount to be transferred
-        // ctx.accounts.transfer_instruction.amount = net_transfer;
-        
-        // Step: Transfer tokens to the shared lock pool token account
-        let cpi_ctx_locking = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.source_wallet.to_account_info(),
-                to: ctx.accounts.lock_pool_token_account.to_account_info(),
-                authority: ctx.accounts.authority.clone(),
-            },
-            ctx.signer_seeds, // PDA signer
-        );
-        token::transfer(cpi_ctx_locking, net_transfer)?;
-        
-
-        // Check if the user is already in the LockPoolState
-        if let Some(user) = lock_pool.users.iter_mut().find(|u| u.user_wallet == ctx.accounts.user_wallet.key()) {
-            // Update existing user's locked tokens
-            user.total_tokens += net_transfer;
-            user.locked_tokens += net_transfer;
-        } else {
-            // Add new user entry to the LockPool
-            lock_pool.users.push(UserLockInfo {
-                user_wallet: ctx.accounts.user_wallet.key(), // Buyer’s wallet
-                total_tokens: locked_tokens,                 // Total tokens purchased
-                locked_tokens,                              // Tokens still locked
-                unlocked_tokens: 0,                         // Tokens immediately unlocked
-            });
-        }
-
-    }
-
-    // Allow the transfer to proceed
-    Ok(())
-}
-const MAX_HOLD_AMOUNT: u64 = 50_000_000;
-const YOUR_PROJECT_WALLET: Pubkey = Pubkey::new_from_array([34o4N3JLTxGsqHtFqwpsPDRyimmhbGrUNhhro6xGKhAS]);
-const YOUR_MARKET_WALLET: Pubkey = Pubkey::new_from_array([Fn3Co7FJyMHM6RpPD74TX4Ah2ShLhyNHzNie19jNg8BG]);
-// Known DEX program IDs
-const SERUM_DEX_PROGRAM_ID_DEV_1: Pubkey = Pubkey::new_from_array([DESVgJVGajEgKGXhb6XmqDHGz3VjdgP7rEVESBgxmroY]); ///DevNET
-const SERUM_DEX_PROGRAM_ID_MAIN_1: Pubkey = Pubkey::new_from_array([9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin]); ///MainNET
-const RAYDIUM_PROGRAM_ID_DEV_1: Pubkey = Pubkey::new_from_array([CPMDWBwJDtYax9qW7AyRuVC19Cc4L4Vcy4n2BHAbHkCW]);    ///DevNET
-const RAYDIUM_PROGRAM_ID_DEV_2: Pubkey = Pubkey::new_from_array([HWy1jotHpo6UqeQxx49dpYYdQB8wj9Qk9MdxwjLvDHB8]);    ///DevNET
-const RAYDIUM_PROGRAM_ID_DEV_3: Pubkey = Pubkey::new_from_array([DDg4VmQaJV9ogWce7LpcjBA9bv22wRp5uaTPa5pGjijF]);    ///DevNET
-const RAYDIUM_PROGRAM_ID_DEV_4: Pubkey = Pubkey::new_from_array([devi51mZmdwUJGU9hjN27vEz64Gps7uUefqxg27EAtH]);    ///DevNET
-const RAYDIUM_PROGRAM_ID_DEV_5: Pubkey = Pubkey::new_from_array([85BFyr98MbCUU9MVTEgzx1nbhWACbJqLzho6zd6DZcWL]);    ///DevNET
-const RAYDIUM_PROGRAM_ID_DEV_6: Pubkey = Pubkey::new_from_array([EcLzTrNg9V7qhcdyXDe2qjtPkiGzDM2UbdRaeaadU5r2]);    ///DevNET
-const RAYDIUM_PROGRAM_ID_DEV_7: Pubkey = Pubkey::new_from_array([BVChZ3XFEwTMUk1o9i3HAf91H6mFxSwa5X2wFAWhYPhU]);    ///DevNET
-const RAYDIUM_PROGRAM_ID_MAIN_1: Pubkey = Pubkey::new_from_array([CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C]);    ///MainNET
-const RAYDIUM_PROGRAM_ID_MAIN_2: Pubkey = Pubkey::new_from_array([675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8]);    ///MainNET
-const RAYDIUM_PROGRAM_ID_MAIN_3: Pubkey = Pubkey::new_from_array([5quBtoiQqxF9Jv6KYKctB59NT3gtJD2Y65kdnB1Uev3h]);    ///MainNET
-const RAYDIUM_PROGRAM_ID_MAIN_4: Pubkey = Pubkey::new_from_array([CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK]);    ///MainNET
-const RAYDIUM_PROGRAM_ID_MAIN_5: Pubkey = Pubkey::new_from_array([routeUGWgWzqBWFcrCfv8tritsqukccJPu3q5GPP3xS]);    ///MainNET
-const RAYDIUM_PROGRAM_ID_MAIN_6: Pubkey = Pubkey::new_from_array([EhhTKczWMGQt46ynNeRX1WfeagwwJd7ufHvCDjRxjo5Q]);    ///MainNET
-const RAYDIUM_PROGRAM_ID_MAIN_7: Pubkey = Pubkey::new_from_array([9KEPoZmtHUrBbhWN1v1KWLMkkvwY6WLtAVUCPRtRjP4z]);    ///MainNET
-const RAYDIUM_PROGRAM_ID_MAIN_8: Pubkey = Pubkey::new_from_array([FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG]);    ///MainNET
-const RAYDIUM_PROGRAM_ID_MAIN_9: Pubkey = Pubkey::new_from_array([9HzJyW1qZsEiSfMUf6L2jo3CcTKAyBmSyKdwQeYisHrC]);    ///MainNET
-const ORCA_PROGRAM_ID_MAIN_1: Pubkey = Pubkey::new_from_array([whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc]);    ///MainNET
-
-// Helper function to determine if a transaction is associated with a DEX
-fn is_dex_transaction(source_program_id: &Pubkey, destination_program_id: &Pubkey) -> bool {
-    // Known DEX program IDs
-    const KNOWN_DEX_PROGRAMS: [&Pubkey; 19] = [
-        &SERUM_DEX_PROGRAM_ID_DEV_1,
-        &SERUM_DEX_PROGRAM_ID_MAIN_1,
-        &RAYDIUM_PROGRAM_ID_DEV_1,
-        &RAYDIUM_PROGRAM_ID_DEV_2,
-        &RAYDIUM_PROGRAM_ID_DEV_3,
-        &RAYDIUM_PROGRAM_ID_DEV_4,
-        &RAYDIUM_PROGRAM_ID_DEV_5,
-        &RAYDIUM_PROGRAM_ID_DEV_6,
-        &RAYDIUM_PROGRAM_ID_DEV_7,
-        &RAYDIUM_PROGRAM_ID_MAIN_1,
-        &RAYDIUM_PROGRAM_ID_MAIN_2,
-        &RAYDIUM_PROGRAM_ID_MAIN_3,
-        &RAYDIUM_PROGRAM_ID_MAIN_4,
-        &RAYDIUM_PROGRAM_ID_MAIN_5,
-        &RAYDIUM_PROGRAM_ID_MAIN_6,
-        &RAYDIUM_PROGRAM_ID_MAIN_7,
-        &RAYDIUM_PROGRAM_ID_MAIN_8,
-        &RAYDIUM_PROGRAM_ID_MAIN_9,
-        &ORCA_PROGRAM_ID_MAIN_1,
-    ];
-
-    // Check if source or destination is a known DEX program
-    KNOWN_DEX_PROGRAMS.contains(source_program_id) || KNOWN_DEX_PROGRAMS.contains(destination_program_id)
-}
-
-
-// =====================================================Error=============================================
-
-
-#[error_code]
-pub enum CustomError {
-    #[msg("Unauthorized: You do not have permission to call this method.")]
-    Unauthorized,
-    #[msg("Milestone has not been reached for token unlock.")]
-    MilestoneNotReached,
-    #[msg("Conditions not met: Final milestone or 3-month unlock period.")]
-    UnlockTooSoon,
-    #[msg("Invalid Token Amount")]
-    InvalidTokenAmount,
-    #[msg("Insufficient Pool Balance")]
-    InsufficientPoolBalance,
-    #[msg("Max hold amount exceeded")]
-    MaxHoldExceeded,
-    #[msg("Already Full Unlocked")]
-    FullUnlockAlreadyExecuted,
-    #[msg("Insufficient Pool Balance")]
-    InsufficientPoolBalance,
-    #[msg("Lock accounts have already been initialized.")]
-    AlreadyInitialized,
-}
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, get_associated_token_address, AssociatedToken};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer}; 
+
+declare_id!("6vxBssG3FvWset4jv3STQGGnq3mTqkkD2BSbYC5s7j89");
+
+
+#[program]
+pub mod hotwings {
+    use anchor_spl::token;
+
+    use super::*;
+
+    pub fn create_pool(
+        ctx: Context<CreatePool>,
+        timelock_delay: i64, // Seconds between queueing and executing a configuration or admin change
+        max_holders: u64,    // Hard cap on UserLockInfo records
+    ) -> Result<()> {
+        require!(timelock_delay >= MIN_TIMELOCK_DELAY, CustomError::InvalidTimelockDelay);
+        require!(max_holders > 0, CustomError::InvalidMaxHolders);
+
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+        lock_pool.version = LOCK_POOL_VERSION;
+        lock_pool.phase = PoolPhase::Loading;
+        lock_pool.is_max_hold_limit_active = true; // Lifted by `finalize_unlock`
+        lock_pool.mint = ctx.accounts.mint.key();
+        lock_pool.admin_wallet = ctx.accounts.admin_wallet.key();
+        // Every role starts with the admin until a queued `ConfigChange::Roles` hands them out
+        lock_pool.milestone_keeper = ctx.accounts.admin_wallet.key();
+        lock_pool.treasury = ctx.accounts.admin_wallet.key();
+        lock_pool.pauser = ctx.accounts.admin_wallet.key();
+        lock_pool.lock_pool_token_account = ctx.accounts.lock_pool_token_account.key();
+        lock_pool.token_pool_account = ctx.accounts.token_pool_account.key();
+        lock_pool.vault_authority_bump = ctx.bumps.pda;
+        lock_pool.bump = ctx.bumps.lock_pool_account;
+        lock_pool.timelock_delay = timelock_delay;
+        lock_pool.max_holders = max_holders;
+
+        Ok(())
+    }
+
+    // Creates the UserLockInfo records of the next legacy holders, one per remaining account, in
+    // merged first-appearance order; call until every holder has a record (once with none for an empty pool)
+    pub fn migrate_pool_holders<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigratePoolHolders<'info>>,
+        legacy_layout: LegacyLayout, // Layout the pool account was written with
+    ) -> Result<()> {
+        let lock_pool_info = ctx.accounts.lock_pool_account.to_account_info();
+        let lock_pool_key = lock_pool_info.key();
+        let legacy = read_legacy_pool(&lock_pool_info.try_borrow_data()?, legacy_layout)?;
+        let holders = merge_legacy_users(&legacy.users)?;
+
+        let migration = &mut ctx.accounts.pool_migration;
+        if migration.lock_pool == Pubkey::default() {
+            migration.lock_pool = lock_pool_key;
+            migration.legacy_layout = legacy_layout;
+            migration.bump = ctx.bumps.pool_migration;
+        }
+        // ✅ Security Check: Every batch must decode the pool with the same layout
+        require!(migration.legacy_layout == legacy_layout, CustomError::InvalidLegacyLayout);
+
+        let start = migration.holder_cursor as usize;
+        let end = start
+            .checked_add(ctx.remaining_accounts.len())
+            .ok_or(CustomError::MathOverflow)?;
+        require!(end <= holders.len(), CustomError::InvalidUserLockAccount);
+
+        for (holder, user_lock_info) in holders[start..end].iter().zip(ctx.remaining_accounts.iter()) {
+            let index = migration.record_holder(holder)?;
+            create_user_lock_account(
+                lock_pool_key,
+                NewUserLock {
+                    index,
+                    wallet: holder.user_wallet,
+                    category: InvestorCategory::Presale, // The legacy layouts predate investor categories
+                    token_amount: holder.total_tokens,
+                },
+                user_lock_info,
+                &ctx.accounts.upgrade_authority.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+            )?;
+            let mut user_lock = load_user_lock_account(&lock_pool_key, user_lock_info)?;
+            user_lock.release(holder.unlocked_tokens)?;
+            user_lock.exit(&crate::ID)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn migrate_pool(
+        ctx: Context<MigratePool>,
+        legacy_layout: LegacyLayout, // Layout the pool account was written with
+        timelock_delay: i64,         // Seconds between queueing and executing a configuration or admin change
+        max_holders: u64,            // Hard cap on UserLockInfo records (must fit the legacy user list)
+    ) -> Result<()> {
+        require!(timelock_delay >= MIN_TIMELOCK_DELAY, CustomError::InvalidTimelockDelay);
+        require!(max_holders > 0, CustomError::InvalidMaxHolders);
+
+        let lock_pool_info = ctx.accounts.lock_pool_account.to_account_info();
+        let lock_pool_key = lock_pool_info.key();
+        let legacy = read_legacy_pool(&lock_pool_info.try_borrow_data()?, legacy_layout)?;
+        let migration = &ctx.accounts.pool_migration;
+        require!(migration.legacy_layout == legacy_layout, CustomError::InvalidLegacyLayout);
+
+        let lock_pool = convert_legacy_pool(
+            &legacy,
+            migration,
+            MigratedPoolSettings {
+                mint: ctx.accounts.mint.key(),
+                admin_wallet: ctx.accounts.admin_wallet.key(),
+                lock_pool_token_account: ctx.accounts.lock_pool_token_account.key(),
+                token_pool_account: ctx.accounts.token_pool_account.key(),
+                vault_authority_bump: ctx.bumps.pda,
+                timelock_delay,
+                max_holders,
+            },
+        )?;
+
+        // Grow the account to the current layout, topping up rent from the upgrade authority
+        let space = 8 + LockPool::INIT_SPACE;
+        let rent_due = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(lock_pool_info.lamports());
+        if rent_due > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.upgrade_authority.to_account_info(),
+                        to: lock_pool_info.clone(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+        if lock_pool_info.data_len() < space {
+            lock_pool_info.realloc(space, true)?;
+        }
+        let mut data = lock_pool_info.try_borrow_mut_data()?;
+        data.fill(0);
+        lock_pool.try_serialize(&mut &mut data[..])?;
+
+        emit!(PoolMigrated {
+            lock_pool: lock_pool_key,
+            legacy_layout,
+            holder_count: lock_pool.holder_count,
+            total_locked: lock_pool.total_locked,
+            legacy_total_locked: legacy.total_locked,
+            total_locked_drift: legacy.total_locked as i128 - lock_pool.total_locked as i128,
+        });
+
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+        let previous_admin = lock_pool.admin_wallet;
+        lock_pool.admin_wallet = ctx.accounts.pending_admin_wallet.key();
+        lock_pool.pending_admin_wallet = Pubkey::default();
+
+        emit!(AdminTransferred {
+            lock_pool: lock_pool.key(),
+            previous_admin_wallet: previous_admin,
+            admin_wallet: lock_pool.admin_wallet,
+        });
+
+        Ok(())
+    }
+
+    // Emergency stop: the pauser can halt any scope at once; lifting a pause is a timelocked admin change
+    pub fn pause(ctx: Context<Pause>, flags: u8) -> Result<()> {
+        require!(flags != 0 && flags & !PAUSE_ALL == 0, CustomError::InvalidPauseFlags);
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+        lock_pool.paused_flags |= flags;
+
+        emit!(PauseUpdated {
+            lock_pool: lock_pool.key(),
+            paused_flags: lock_pool.paused_flags,
+        });
+
+        Ok(())
+    }
+
+    pub fn queue_change(ctx: Context<QueueChange>, change: ConfigChange) -> Result<()> {
+        // Reject invalid changes up front so the queue only holds executable entries
+        match &change {
+            ConfigChange::PoolConfig(params) => validate_pool_config(params)?,
+            ConfigChange::TimelockDelay { delay } => {
+                require!(*delay >= MIN_TIMELOCK_DELAY, CustomError::InvalidTimelockDelay)
+            }
+            ConfigChange::Unpause { flags } => {
+                require!(*flags != 0 && *flags & !PAUSE_ALL == 0, CustomError::InvalidPauseFlags)
+            }
+            ConfigChange::MaxHolders { max_holders } => {
+                require!(*max_holders > 0, CustomError::InvalidMaxHolders);
+                require!(*max_holders >= ctx.accounts.lock_pool_account.holder_count, CustomError::InvalidMaxHolders)
+            }
+            ConfigChange::Admin { .. } | ConfigChange::Roles { .. } => {}
+        }
+
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+        let now = Clock::get()?.unix_timestamp;
+
+        let queued_change = &mut ctx.accounts.queued_change;
+        queued_change.lock_pool = lock_pool.key();
+        queued_change.index = lock_pool.queued_change_count;
+        queued_change.change = change;
+        queued_change.queued_at = now;
+        queued_change.eta = now.checked_add(lock_pool.timelock_delay).ok_or(CustomError::MathOverflow)?;
+        queued_change.status = QueuedChangeStatus::Queued;
+        queued_change.bump = ctx.bumps.queued_change;
+        lock_pool.queued_change_count = lock_pool.queued_change_count.checked_add(1).ok_or(CustomError::MathOverflow)?;
+
+        emit!(ChangeQueued {
+            lock_pool: queued_change.lock_pool,
+            queued_change: queued_change.key(),
+            change: queued_change.change.clone(),
+            eta: queued_change.eta,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless once `eta` has passed: the admin already committed to the change when queueing it
+    pub fn execute_change(ctx: Context<ExecuteChange>) -> Result<()> {
+        let queued_change = &mut ctx.accounts.queued_change;
+        require!(queued_change.status == QueuedChangeStatus::Queued, CustomError::ChangeNotQueued);
+        require!(Clock::get()?.unix_timestamp >= queued_change.eta, CustomError::TimelockNotElapsed);
+
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+        let lock_pool_key = lock_pool.key();
+        match &queued_change.change {
+            ConfigChange::PoolConfig(params) => ctx
+                .accounts
+                .pool_config
+                .as_mut()
+                .ok_or(CustomError::PoolConfigRequired)?
+                .apply(params),
+            ConfigChange::Admin { new_admin } => {
+                // The new admin still has to `accept_admin`
+                lock_pool.pending_admin_wallet = *new_admin;
+                emit!(AdminProposed {
+                    lock_pool: lock_pool_key,
+                    admin_wallet: lock_pool.admin_wallet,
+                    pending_admin_wallet: *new_admin,
+                });
+            }
+            ConfigChange::Roles { milestone_keeper, treasury, pauser } => {
+                lock_pool.milestone_keeper = *milestone_keeper;
+                lock_pool.treasury = *treasury;
+                lock_pool.pauser = *pauser;
+                emit!(RolesUpdated {
+                    lock_pool: lock_pool_key,
+                    milestone_keeper: *milestone_keeper,
+                    treasury: *treasury,
+                    pauser: *pauser,
+                });
+            }
+            ConfigChange::TimelockDelay { delay } => lock_pool.timelock_delay = *delay,
+            ConfigChange::MaxHolders { max_holders } => {
+                // ✅ Security Check: Holders registered while the change was queued must still fit
+                require!(*max_holders >= lock_pool.holder_count, CustomError::InvalidMaxHolders);
+                lock_pool.max_holders = *max_holders;
+            }
+            ConfigChange::Unpause { flags } => {
+                lock_pool.paused_flags &= !*flags;
+                emit!(PauseUpdated {
+                    lock_pool: lock_pool_key,
+                    paused_flags: lock_pool.paused_flags,
+                });
+            }
+        }
+        queued_change.status = QueuedChangeStatus::Executed;
+
+        emit!(ChangeExecuted {
+            lock_pool: lock_pool_key,
+            queued_change: queued_change.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_change(ctx: Context<CancelChange>) -> Result<()> {
+        let queued_change = &mut ctx.accounts.queued_change;
+        require!(queued_change.status == QueuedChangeStatus::Queued, CustomError::ChangeNotQueued);
+        queued_change.status = QueuedChangeStatus::Cancelled;
+
+        emit!(ChangeCancelled {
+            lock_pool: queued_change.lock_pool,
+            queued_change: queued_change.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn create_multisig(ctx: Context<CreateMultisig>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        // ✅ Security: The multisig is installed once; from then on gated instructions need its approval
+        require!(
+            ctx.accounts.lock_pool_account.multisig == Pubkey::default(),
+            CustomError::MultisigAlreadySet
+        );
+        require!(
+            !signers.is_empty()
+                && signers.len() <= MAX_MULTISIG_SIGNERS
+                && threshold > 0
+                && threshold as usize <= signers.len(),
+            CustomError::InvalidMultisig
+        );
+        for (i, signer) in signers.iter().enumerate() {
+            require!(!signers[..i].contains(signer), CustomError::InvalidMultisig);
+        }
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.lock_pool = ctx.accounts.lock_pool_account.key();
+        multisig.signers = signers;
+        multisig.threshold = threshold;
+        multisig.proposal_count = 0;
+        multisig.bump = ctx.bumps.multisig;
+        ctx.accounts.lock_pool_account.multisig = multisig.key();
+
+        Ok(())
+    }
+
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        action: ProposalAction, // Instruction (and parameters) the proposal authorizes
+        expires_in: i64,        // Seconds the proposal stays open for approvals and execution
+    ) -> Result<()> {
+        require!(expires_in > 0, CustomError::InvalidMultisig);
+        let multisig = &mut ctx.accounts.multisig;
+        let signer_index = multisig_signer_index(multisig, &ctx.accounts.proposer.key())?;
+        let now = Clock::get()?.unix_timestamp;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.multisig = multisig.key();
+        proposal.index = multisig.proposal_count;
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.action = action;
+        proposal.approvals = 1 << signer_index; // The proposer approves their own proposal
+        proposal.threshold = multisig.threshold;
+        proposal.created_at = now;
+        proposal.expires_at = now.checked_add(expires_in).ok_or(CustomError::MathOverflow)?;
+        proposal.status = ProposalStatus::Active;
+        proposal.bump = ctx.bumps.proposal;
+        multisig.proposal_count = multisig.proposal_count.checked_add(1).ok_or(CustomError::MathOverflow)?;
+
+        emit!(ProposalCreated {
+            multisig: proposal.multisig,
+            proposal: proposal.key(),
+            proposer: proposal.proposer,
+            action: proposal.action.clone(),
+            expires_at: proposal.expires_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        let signer_index = multisig_signer_index(&ctx.accounts.multisig, &ctx.accounts.signer.key())?;
+        let proposal = &mut ctx.accounts.proposal;
+
+        require!(proposal.status == ProposalStatus::Active, CustomError::ProposalNotActive);
+        require!(Clock::get()?.unix_timestamp < proposal.expires_at, CustomError::ProposalExpired);
+        proposal.approvals |= 1 << signer_index;
+
+        emit!(ProposalApproved {
+            multisig: proposal.multisig,
+            proposal: proposal.key(),
+            signer: ctx.accounts.signer.key(),
+            approvals: proposal.approvals.count_ones() as u8,
+            threshold: proposal.threshold,
+        });
+
+        Ok(())
+    }
+
+    // A signer may cancel an open proposal; once expired anyone may close it out as Expired
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let proposal = &mut ctx.accounts.proposal;
+        require!(proposal.status == ProposalStatus::Active, CustomError::ProposalNotActive);
+
+        if now >= proposal.expires_at {
+            proposal.status = ProposalStatus::Expired;
+        } else {
+            multisig_signer_index(&ctx.accounts.multisig, &ctx.accounts.signer.key())?;
+            proposal.status = ProposalStatus::Cancelled;
+        }
+
+        emit!(ProposalClosed {
+            multisig: proposal.multisig,
+            proposal: proposal.key(),
+            status: proposal.status,
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_pool_config(
+        ctx: Context<InitializePoolConfig>,
+        params: PoolConfigParams, // Milestone table, tax rates, hold limit and lock duration
+    ) -> Result<()> {
+        // ✅ Security: Pool rules are fixed before the pool is sealed
+        require!(
+            ctx.accounts.lock_pool_account.phase == PoolPhase::Loading,
+            CustomError::AlreadyInitialized
+        );
+        validate_pool_config(&params)?;
+
+        let pool_config = &mut ctx.accounts.pool_config;
+        pool_config.lock_pool = ctx.accounts.lock_pool_account.key();
+        pool_config.apply(&params);
+        pool_config.bump = ctx.bumps.pool_config;
+
+        Ok(())
+    }
+
+    pub fn initialize_lock_accounts<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitializeLockAccounts<'info>>,
+        users: Vec<InvestorInfo>, // First batch of users
+    ) -> Result<()> {
+        // ✅ Security: Check if pool is already initialized
+        let lock_pool = &ctx.accounts.lock_pool_account;
+        require!(
+            lock_pool.phase == PoolPhase::Loading && lock_pool.holder_count == 0,
+            CustomError::AlreadyInitialized
+        );
+
+        append_investor_batch(ctx.accounts, ctx.remaining_accounts, &users)
+    }
+
+    pub fn append_investors<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitializeLockAccounts<'info>>,
+        users: Vec<InvestorInfo>, // Next batch of users
+    ) -> Result<()> {
+        // ✅ Security: The investor list is only mutable while the pool is loading
+        require!(
+            ctx.accounts.lock_pool_account.phase == PoolPhase::Loading,
+            CustomError::PoolSealed
+        );
+
+        append_investor_batch(ctx.accounts, ctx.remaining_accounts, &users)
+    }
+
+    pub fn seal_pool(ctx: Context<SealPool>) -> Result<()> {
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+
+        require!(lock_pool.phase == PoolPhase::Loading, CustomError::PoolSealed);
+        // ✅ Security Check: Every locked token must be backed by the vault (surplus is swept by `reclaim_dust`)
+        require!(
+            ctx.accounts.lock_pool_token_account.amount >= lock_pool.total_locked,
+            CustomError::VaultBalanceMismatch
+        );
+
+        // Fix `start_time`; the investor list is immutable from here on.
+        // A pool carried over by `migrate_pool` keeps its original start.
+        if lock_pool.start_time == 0 {
+            lock_pool.start_time = Clock::get()?.unix_timestamp;
+        }
+        lock_pool.phase = PoolPhase::Sealed;
+
+        Ok(())
+    }
+
+    pub fn unlock_tokens(ctx: Context<UnlockTokens>) -> Result<()> {
+        // Derive market cap on-chain from the configured price feed and circulating supply
+        let clock = Clock::get()?;
+        let price = load_price(&ctx.accounts.price_feed, &ctx.accounts.pool_config, clock.unix_timestamp)?;
+        let circulating_supply = ctx
+            .accounts
+            .mint
+            .supply
+            .saturating_sub(ctx.accounts.lock_pool_token_account.amount); // Locked tokens do not circulate
+        let market_cap = market_cap_from_price(circulating_supply, ctx.accounts.mint.decimals, &price)?;
+
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+        require!(lock_pool.phase == PoolPhase::Sealed, CustomError::PoolNotSealed);
+        lock_pool.require_not_paused(PAUSE_UNLOCKS)?;
+    
+        if let Some(milestone) = observe_market_cap(lock_pool, &ctx.accounts.pool_config, market_cap, clock.unix_timestamp) {
+            // Observations are free; only committing a milestone spends the multisig proposal
+            consume_proposal(lock_pool, ctx.accounts.proposal.as_mut(), ProposalAction::UnlockTokens, clock.unix_timestamp)?;
+            start_milestone_job(lock_pool, &ctx.accounts.pool_config, milestone, market_cap, clock.unix_timestamp);
+        }
+    
+        Ok(())
+    }
+
+    pub fn unlock_tokens_from_amm(ctx: Context<UnlockTokensFromAmm>) -> Result<()> {
+        // Derive market cap on-chain from the configured AMM pool reserves and circulating supply
+        let clock = Clock::get()?;
+        let spot_price = load_amm_spot_price(
+            &ctx.accounts.amm_pool,
+            &ctx.accounts.mint.key(),
+            &ctx.accounts.quote_mint.key(),
+            &ctx.accounts.token_vault,
+            &ctx.accounts.quote_vault,
+        )?;
+        let circulating_supply = ctx
+            .accounts
+            .mint
+            .supply
+            .saturating_sub(ctx.accounts.lock_pool_token_account.amount); // Locked tokens do not circulate
+        let market_cap = market_cap_from_spot_price(circulating_supply, ctx.accounts.quote_mint.decimals, &spot_price)?;
+
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+        require!(lock_pool.phase == PoolPhase::Sealed, CustomError::PoolNotSealed);
+        lock_pool.require_not_paused(PAUSE_UNLOCKS)?;
+
+        if let Some(milestone) = observe_market_cap(lock_pool, &ctx.accounts.pool_config, market_cap, clock.unix_timestamp) {
+            // Observations are free; only committing a milestone spends the multisig proposal
+            consume_proposal(lock_pool, ctx.accounts.proposal.as_mut(), ProposalAction::UnlockTokensFromAmm, clock.unix_timestamp)?;
+            start_milestone_job(lock_pool, &ctx.accounts.pool_config, milestone, market_cap, clock.unix_timestamp);
+        }
+
+        Ok(())
+    }
+
+    pub fn initialize_manual_price_feed(ctx: Context<InitializeManualPriceFeed>) -> Result<()> {
+        // ✅ Security: Mainnet builds only take prices from Pyth
+        require!(cfg!(feature = "localnet"), CustomError::ManualPriceFeedDisabled);
+
+        let price_feed = &mut ctx.accounts.price_feed;
+        price_feed.authority = ctx.accounts.authority.key();
+        price_feed.bump = ctx.bumps.price_feed;
+        Ok(())
+    }
+
+    pub fn update_manual_price(
+        ctx: Context<UpdateManualPrice>,
+        price: i64,
+        conf: u64,
+        expo: i32,
+    ) -> Result<()> {
+        require!(cfg!(feature = "localnet"), CustomError::ManualPriceFeedDisabled);
+        require!(price > 0, CustomError::InvalidPriceFeed);
+
+        let price_feed = &mut ctx.accounts.price_feed;
+        price_feed.price = price;
+        price_feed.conf = conf;
+        price_feed.expo = expo;
+        price_feed.publish_time = Clock::get()?.unix_timestamp; // Published now
+        Ok(())
+    }
+
+    pub fn full_unlock(ctx: Context<FullUnlock>) -> Result<()> {
+        consume_proposal(
+            &ctx.accounts.lock_pool_account,
+            ctx.accounts.proposal.as_mut(),
+            ProposalAction::FullUnlock,
+            ctx.accounts.clock.unix_timestamp,
+        )?;
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+    
+        // Ensure that the full unlock has not been executed yet
+        require!(lock_pool.phase == PoolPhase::Sealed, CustomError::PoolNotSealed);
+        lock_pool.require_not_paused(PAUSE_UNLOCKS)?;
+        require!(!lock_pool.full_unlock_executed, CustomError::FullUnlockAlreadyExecuted);
+    
+        // Get the current Solana cluster time
+        let current_time = ctx.accounts.clock.unix_timestamp;
+    
+        // Ensure that the configured lock duration has passed since `start_time`
+        let full_unlock_time = lock_pool
+            .start_time
+            .checked_add(ctx.accounts.pool_config.full_unlock_delay)
+            .ok_or(CustomError::MathOverflow)?;
+        require!(current_time >= full_unlock_time, CustomError::UnlockTooSoon);
+    
+        require!(!lock_pool.unlock_in_progress, CustomError::UnlockInProgress);
+    
+        // Open a distribution job; full unlock is marked executed once every user has been processed
+        let lock_pool_key = lock_pool.key();
+        lock_pool.pending_full_unlock = true;
+        start_unlock_job(lock_pool_key, lock_pool, &ctx.accounts.pool_config, current_time);
+    
+        Ok(())
+    }
+
+    pub fn process_unlock_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ProcessUnlockBatch<'info>>,
+        start: u64,
+        count: u64,
+    ) -> Result<()> {
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+        let lock_pool_key = lock_pool.key();
+
+        // ✅ Security Check: Batches must resume exactly where the previous one stopped
+        require!(lock_pool.unlock_in_progress, CustomError::NoUnlockInProgress);
+        lock_pool.require_not_paused(PAUSE_UNLOCKS)?;
+        require!(start == lock_pool.unlock_cursor, CustomError::BatchOutOfOrder);
+        let end = start.checked_add(count).ok_or(CustomError::MathOverflow)?;
+        require!(
+            count > 0 && end <= lock_pool.holder_count,
+            CustomError::BatchOutOfOrder
+        );
+
+        // Remaining accounts: (UserLockInfo PDA, user wallet, user associated token account) per investor,
+        // in index order
+        require!(
+            Some(ctx.remaining_accounts.len() as u64) == count.checked_mul(3),
+            CustomError::UserLockAccountsMismatch
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+
+        for (offset, accounts) in ctx.remaining_accounts.chunks(3).enumerate() {
+            let mut user = load_user_lock_account(&lock_pool_key, &accounts[0])?;
+            require!(user.index == start + offset as u64, CustomError::BatchOutOfOrder);
+
+            // Amount released by the pending milestone (or everything for the full unlock)
+            let newly_unlocked = pending_claimable_amount(&ctx.accounts.pool_config, lock_pool, &user, current_time)?;
+
+            // Check to avoid unnecessary processing
+            if newly_unlocked > 0 {
+                user.release(newly_unlocked)?;      // Move tokens from locked to unlocked
+                lock_pool.release(newly_unlocked)?; // Tokens leave the vault
+                user.exit(&crate::ID)?;             // Persist the investor record
+
+                // Resolve the investor's associated token account, creating it if needed
+                require_keys_eq!(accounts[1].key(), user.user_wallet, CustomError::InvalidRecipientTokenAccount);
+                resolve_recipient_token_account(
+                    &accounts[1],
+                    &accounts[2],
+                    &ctx.accounts.mint,
+                    &ctx.accounts.milestone_keeper.to_account_info(),
+                    &ctx.accounts.token_program,
+                    &ctx.accounts.associated_token_program,
+                    &ctx.accounts.system_program,
+                )?;
+
+                // Transfer unlocked tokens to the user
+                transfer_from_vault(
+                    &ctx.accounts.token_program,
+                    ctx.accounts.lock_pool_token_account.to_account_info(),
+                    accounts[2].clone(),
+                    &ctx.accounts.pda,
+                    &lock_pool_key,
+                    lock_pool.vault_authority_bump,
+                    newly_unlocked,
+                )?;
+
+                emit!(TokensUnlocked {
+                    lock_pool: lock_pool_key,
+                    user_wallet: user.user_wallet,
+                    amount: newly_unlocked,
+                    user_unlocked_tokens: user.unlocked_tokens,
+                    user_locked_tokens: user.locked_tokens,
+                });
+            }
+        }
+
+        // Advance the cursor and commit the job once the last user has been processed
+        lock_pool.unlock_cursor = end;
+        if lock_pool.unlock_cursor == lock_pool.holder_count {
+            complete_unlock_job(lock_pool_key, lock_pool, &ctx.accounts.pool_config, current_time);
+        }
+
+        Ok(())
+    }
+
+    // Rolls back an open job (e.g. stuck on a frozen recipient): the pending milestone or full unlock is
+    // not committed and has to be reopened from the first holder
+    pub fn abort_unlock_job(ctx: Context<AbortUnlockJob>) -> Result<()> {
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+        require!(lock_pool.unlock_in_progress, CustomError::NoUnlockInProgress);
+
+        emit!(UnlockJobAborted {
+            lock_pool: lock_pool.key(),
+            pending_milestone: lock_pool.pending_milestone,
+            pending_full_unlock: lock_pool.pending_full_unlock,
+            unlock_cursor: lock_pool.unlock_cursor,
+            holder_count: lock_pool.holder_count,
+        });
+        close_unlock_job(lock_pool);
+
+        Ok(())
+    }
+
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        let claimed = settle_claim(
+            &ctx.accounts.pool_config,
+            &mut ctx.accounts.lock_pool_account,
+            &mut ctx.accounts.user_lock_account,
+            &ctx.accounts.lock_pool_token_account,
+            &ctx.accounts.user_token_account,
+            &ctx.accounts.pda,
+            &ctx.accounts.token_program,
+        )?;
+        require!(claimed > 0, CustomError::NothingToClaim);
+
+        Ok(())
+    }
+
+    pub fn commit_allocation_root(
+        ctx: Context<CommitAllocationRoot>,
+        root: [u8; 32],    // Merkle root of (wallet, amount, category) leaves
+        total_amount: u64, // Sum of all leaf amounts, funded into the lock pool now
+    ) -> Result<()> {
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+
+        // ✅ Security: The allocation list is committed once and fully funded up front
+        require!(lock_pool.phase == PoolPhase::Loading, CustomError::PoolSealed);
+        require!(lock_pool.allocation_root == [0u8; 32], CustomError::AllocationRootAlreadySet);
+        require!(root != [0u8; 32] && total_amount > 0, CustomError::InvalidTokenAmount);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.source_wallet.to_account_info(), // Admin's source wallet
+            to: ctx.accounts.lock_pool_token_account.to_account_info(), // Centralized lock pool
+            authority: ctx.accounts.admin_wallet.to_account_info(), // Admin wallet signature
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, total_amount)?;
+
+        lock_pool.allocation_root = root;
+        lock_pool.unregistered_allocation = total_amount; // Moved into UserLockInfo records as investors prove their leaf
+        lock_pool.add_locked(total_amount)?;
+
+        emit!(AllocationRootCommitted {
+            lock_pool: lock_pool.key(),
+            root,
+            total_amount,
+            total_locked: lock_pool.total_locked,
+        });
+
+        Ok(())
+    }
+
+    pub fn claim_with_proof(
+        ctx: Context<ClaimWithProof>,
+        amount: u64,
+        category: InvestorCategory,
+        proof: Vec<[u8; 32]>, // Sibling hashes from the leaf up to the root
+    ) -> Result<()> {
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+        let user = &mut ctx.accounts.user_lock_account;
+        let wallet = ctx.accounts.user_wallet.key();
+
+        // ✅ Security Check: Each leaf is registered once and must prove into the committed root
+        require!(!user.allocation_registered, CustomError::AllocationAlreadyRegistered);
+        let leaf = allocation_leaf(&wallet, amount, category);
+        require!(
+            verify_allocation_proof(&proof, &lock_pool.allocation_root, leaf),
+            CustomError::InvalidAllocationProof
+        );
+
+        // Lazily create (or extend) the investor's lock record
+        if user.user_wallet == Pubkey::default() {
+            user.lock_pool = lock_pool.key();
+            user.index = lock_pool.register_holder()?;
+            user.user_wallet = wallet;
+            user.category = category;
+            user.bump = ctx.bumps.user_lock_account;
+        } else {
+            // ✅ Security Check: A wallet has a single vesting schedule
+            require!(user.category == category, CustomError::InvestorCategoryMismatch);
+        }
+        user.allocation_registered = true;
+        user.add_locked(amount)?;
+        lock_pool.unregistered_allocation = lock_pool
+            .unregistered_allocation
+            .checked_sub(amount)
+            .ok_or(CustomError::InvalidAllocationProof)?;
+
+        emit!(InvestorLocked {
+            lock_pool: lock_pool.key(),
+            user_wallet: wallet,
+            amount,
+            user_total_tokens: user.total_tokens,
+            user_locked_tokens: user.locked_tokens,
+            total_locked: lock_pool.total_locked,
+            holder_count: lock_pool.holder_count,
+        });
+
+        // Pay out whatever is already unlocked for the new record
+        settle_claim(
+            &ctx.accounts.pool_config,
+            lock_pool,
+            user,
+            &ctx.accounts.lock_pool_token_account,
+            &ctx.accounts.user_token_account,
+            &ctx.accounts.pda,
+            &ctx.accounts.token_program,
+        )?;
+
+        Ok(())
+    }
+
+    // Permissionless audit: recomputes the pool totals from every UserLockInfo and the vault.
+    // Records are summed in `[start, start + count)` slices into the PoolAudit PDA (`start = 0`
+    // begins a new audit); the invariants are checked once the slice reaching `holder_count` is summed.
+    pub fn verify_pool<'info>(
+        ctx: Context<'_, '_, 'info, 'info, VerifyPool<'info>>,
+        start: u64,
+        count: u64,
+    ) -> Result<PoolTotals> {
+        let lock_pool = &ctx.accounts.lock_pool_account;
+        let lock_pool_key = lock_pool.key();
+        let audit = &mut ctx.accounts.pool_audit;
+
+        if start == 0 {
+            audit.restart(lock_pool_key, lock_pool, ctx.bumps.pool_audit);
+        }
+        // ✅ Security Check: Slices continue the stored sum, so no caller-supplied total is trusted
+        require!(start == audit.cursor, CustomError::BatchOutOfOrder);
+        // ✅ Security Check: Every slice must sum the same pool state the audit started from
+        require!(audit.matches(lock_pool), CustomError::AuditOutdated);
+
+        let end = start.checked_add(count).ok_or(CustomError::MathOverflow)?;
+        require!(end <= lock_pool.holder_count, CustomError::BatchOutOfOrder);
+        // Remaining accounts: the UserLockInfo PDAs of the slice, in index order
+        require!(
+            ctx.remaining_accounts.len() as u64 == count,
+            CustomError::UserLockAccountsMismatch
+        );
+
+        let mut user_locked_sum = audit.user_locked_sum;
+        for (offset, user_lock_info) in ctx.remaining_accounts.iter().enumerate() {
+            let user = load_user_lock_account(&lock_pool_key, user_lock_info)?;
+            // Index order rules out skipped or repeated records
+            require!(user.index == start + offset as u64, CustomError::BatchOutOfOrder);
+            user_locked_sum = user_locked_sum
+                .checked_add(user.locked_tokens)
+                .ok_or(CustomError::MathOverflow)?;
+        }
+        audit.cursor = end;
+        audit.user_locked_sum = user_locked_sum;
+
+        let totals = PoolTotals {
+            total_locked: lock_pool.total_locked,
+            user_locked_sum,
+            unregistered_allocation: lock_pool.unregistered_allocation,
+            vault_balance: ctx.accounts.lock_pool_token_account.amount,
+            holder_count: lock_pool.holder_count,
+            verified_holders: end,
+        };
+
+        // Partial totals: the caller continues with the next slice
+        if end < lock_pool.holder_count {
+            return Ok(totals);
+        }
+
+        // ✅ Security Check: Records (plus allocations not yet claimed) must add up to total_locked
+        require!(
+            user_locked_sum.checked_add(totals.unregistered_allocation) == Some(totals.total_locked),
+            CustomError::LockedSumMismatch
+        );
+        // ✅ Security Check: The vault must back total_locked exactly
+        require!(
+            totals.vault_balance == totals.total_locked,
+            CustomError::VaultBalanceMismatch
+        );
+
+        Ok(totals)
+    }
+
+    // Sweeps vault tokens that back no holder (above `total_locked`) to `destination`
+    pub fn reclaim_dust(ctx: Context<ReclaimDust>) -> Result<()> {
+        consume_proposal(
+            &ctx.accounts.lock_pool_account,
+            ctx.accounts.proposal.as_mut(),
+            ProposalAction::ReclaimDust {
+                destination: ctx.accounts.destination.key(),
+            },
+            Clock::get()?.unix_timestamp,
+        )?;
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+        let lock_pool_key = lock_pool.key();
+
+        // ✅ Security Check: Never touch tokens that back `total_locked`
+        let dust = ctx
+            .accounts
+            .lock_pool_token_account
+            .amount
+            .checked_sub(lock_pool.total_locked)
+            .ok_or(CustomError::VaultBalanceMismatch)?;
+        require!(dust > 0, CustomError::NothingToClaim);
+
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            ctx.accounts.lock_pool_token_account.to_account_info(),
+            ctx.accounts.destination.to_account_info(),
+            &ctx.accounts.pda,
+            &lock_pool_key,
+            lock_pool.vault_authority_bump,
+            dust,
+        )?;
+        lock_pool.dust_reclaimed = lock_pool.dust_reclaimed.checked_add(dust).ok_or(CustomError::MathOverflow)?;
+
+        emit!(DustReclaimed {
+            lock_pool: lock_pool_key,
+            destination: ctx.accounts.destination.key(),
+            amount: dust,
+            total_reclaimed: lock_pool.dust_reclaimed,
+        });
+
+        Ok(())
+    }
+
+    // Co-signed by the treasury, so the sale pool only pays out purchases the project was paid for
+    pub fn purchase_tokens(ctx: Context<PurchaseTokens>, total_paid_tokens: u64) -> Result<()> {
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+    
+        // Error 1: Ensure `total_paid_tokens` is greater than 0
+        require!(lock_pool.phase == PoolPhase::Sealed, CustomError::PoolNotSealed);
+        lock_pool.require_not_paused(PAUSE_PURCHASES)?;
+        require!(total_paid_tokens > 0, CustomError::InvalidTokenAmount);
+        // Determine the percentage of tokens to unlock immediately based on the current milestone
+        let unlock_percentage = milestone_percentage_from_milestone(
+            ctx.accounts.pool_config.schedule(InvestorCategory::Presale),
+            lock_pool.current_milestone,
+        );
+    
+        // Calculate unlocked and locked tokens
+        let unlocked_tokens = mul_div_floor(total_paid_tokens, unlock_percentage as u64, 100)?;
+        let locked_tokens = total_paid_tokens
+            .checked_sub(unlocked_tokens)
+            .ok_or(CustomError::MathOverflow)?;
+
+        // Error 2: Ensure the sale pool has enough tokens for the purchase
+        require!(
+            ctx.accounts.token_pool_account.amount >= total_paid_tokens,
+            CustomError::InsufficientPoolBalance
+        );
+    
+        // Handle unlocked tokens: Transfer `unlocked_tokens` from the sale pool directly to the user's wallet
+        // (the lock vault only holds tokens backing `total_locked`)
+        if unlocked_tokens > 0 {
+            transfer_from_vault(
+                &ctx.accounts.token_program,
+                ctx.accounts.token_pool_account.to_account_info(),
+                ctx.accounts.user_token_account.to_account_info(),
+                &ctx.accounts.pda,
+                &lock_pool.key(),
+                lock_pool.vault_authority_bump,
+                unlocked_tokens,
+            )?;
+        }
+    
+        
+    
+        // Handle locked tokens: Add locked tokens to the LockPool for this user
+        if locked_tokens > 0 {
+            // Step: Transfer tokens from the sale pool to the shared lock pool token account
+            transfer_from_vault(
+                &ctx.accounts.token_program,
+                ctx.accounts.token_pool_account.to_account_info(), // Source is the sale pool
+                ctx.accounts.lock_pool_token_account.to_account_info(), // Destination is Lock Pool Token Account
+                &ctx.accounts.pda, // Authority is program-derived
+                &lock_pool.key(),
+                lock_pool.vault_authority_bump,
+                locked_tokens,
+            )?;
+            lock_pool.add_locked(locked_tokens)?;
+        }
+
+        // Register the buyer's UserLockInfo PDA on first purchase
+        let user = &mut ctx.accounts.user_lock_account;
+        if user.user_wallet == Pubkey::default() {
+            user.lock_pool = lock_pool.key();
+            user.index = lock_pool.register_holder()?;
+            user.user_wallet = ctx.accounts.user_wallet.key(); // Buyer’s wallet
+            user.category = InvestorCategory::Presale; // Market buyers follow the presale curve
+            user.bump = ctx.bumps.user_lock_account;
+        }
+
+        // Book the whole purchase so later claims only pay the vested share not already transferred
+        user.add_purchase(total_paid_tokens, unlocked_tokens)?;
+
+        let user = &ctx.accounts.user_lock_account;
+        emit!(TokensPurchased {
+            lock_pool: lock_pool.key(),
+            user_wallet: ctx.accounts.user_wallet.key(),
+            total_paid_tokens,
+            unlocked_tokens,
+            locked_tokens,
+            user_total_tokens: user.total_tokens,
+            user_locked_tokens: user.locked_tokens,
+            holder_count: lock_pool.holder_count,
+        });
+    
+        Ok(())
+    }
+
+    pub fn finalize_unlock(ctx: Context<FinalizeUnlock>) -> Result<()> {
+        let clock = Clock::get()?; // Get Solana cluster time
+        let current_time = clock.unix_timestamp;
+
+        consume_proposal(
+            &ctx.accounts.lock_pool_account,
+            ctx.accounts.proposal.as_mut(),
+            ProposalAction::FinalizeUnlock {
+                dex_liquidity_wallet: ctx.accounts.dex_liquidity_wallet.key(),
+            },
+            current_time,
+        )?;
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+        let pool_config = &ctx.accounts.pool_config;
+        // `start_time` is only fixed by `seal_pool`; before that the delay below would already have passed
+        require!(lock_pool.phase == PoolPhase::Sealed, CustomError::PoolNotSealed);
+        lock_pool.require_not_paused(PAUSE_AUTO_SELL)?;
+    
+        // Ensure unlock conditions are met: either final milestone or the configured full unlock delay
+        let full_unlock_time = lock_pool
+            .start_time
+            .checked_add(pool_config.full_unlock_delay)
+            .ok_or(CustomError::MathOverflow)?;
+        let unlock_condition_met = lock_pool.current_milestone as usize >= MILESTONE_COUNT
+            || current_time >= full_unlock_time;
+        require!(unlock_condition_met, CustomError::UnlockTooSoon);
+    
+        // Calculate the configured auto-sell amount
+        let auto_sell_tokens = mul_div_floor(
+            ctx.accounts.project_wallet.amount,
+            pool_config.auto_sell_bps as u64,
+            BPS_DENOMINATOR,
+        )?;
+    
+        require!(auto_sell_tokens > 0, CustomError::InvalidTokenAmount);
+    
+        // Perform token transfer for auto-sell
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.project_wallet.to_account_info(),
+            to: ctx.accounts.dex_liquidity_wallet.to_account_info(),
+            authority: ctx.accounts.project_wallet_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, auto_sell_tokens)?;
+    
+        // Deactivate maximum hold limit
+        lock_pool.is_max_hold_limit_active = false;
+
+        emit!(AutoSellExecuted {
+            lock_pool: lock_pool.key(),
+            project_wallet: ctx.accounts.project_wallet.key(),
+            dex_liquidity_wallet: ctx.accounts.dex_liquidity_wallet.key(),
+            amount: auto_sell_tokens,
+            timestamp: current_time,
+        });
+    
+        Ok(())
+    }
+
+
+}
+
+// =======================================================struct=============================================
+
+#[derive(Accounts)]
+pub struct TransferHookContext<'info> {
+    #[account(mut)]
+    pub source_wallet: Account<'info, TokenAccount>, // Source of the transfer
+    #[account(mut)]
+    pub destination_wallet: Account<'info, TokenAccount>, // Destination of the transfer
+    #[account(mut)]
+    pub burn_wallet: Account<'info, TokenAccount>, // Burn Wallet
+    #[account(mut)]
+    pub marketing_wallet: Account<'info, TokenAccount>, // Marketing Wallet
+    /// CHECK: Vault authority PDA `[VAULT_AUTHORITY_SEED, lock_pool]`; signs the tax and lock transfers
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, lock_pool_account.key().as_ref()],
+        bump = lock_pool_account.vault_authority_bump,
+    )]
+    pub authority: AccountInfo<'info>, // PDA for Token Authority
+    pub token_program: Program<'info, Token>, // SPL Token program
+    #[account(
+        mut,
+        has_one = lock_pool_token_account @ CustomError::InvalidVault,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Track all locking data for users
+    #[account(
+        seeds = [POOL_CONFIG_SEED, lock_pool_account.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Account<'info, PoolConfig>, // Pool rules (milestones, taxes, limits)
+    #[account(mut)]
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // Shared lock vault (PDA-owned)
+    #[account(
+        init_if_needed,
+        payer = user_wallet,
+        space = 8 + UserLockInfo::INIT_SPACE,
+        seeds = [USER_LOCK_SEED, lock_pool_account.key().as_ref(), user_wallet.key().as_ref()],
+        bump,
+    )]
+    pub user_lock_account: Account<'info, UserLockInfo>, // Buyer's per-wallet lock record
+    #[account(mut)]
+    pub user_wallet: Signer<'info>, // User wallet
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    #[account(
+        init,
+        payer = admin_wallet,
+        space = 8 + LockPool::INIT_SPACE,
+        seeds = [LOCK_POOL_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // One lock pool per token mint
+    pub mint: Account<'info, Mint>, // Token locked by the pool
+    /// CHECK: Vault authority PDA `[VAULT_AUTHORITY_SEED, lock_pool]`; only its bump is recorded
+    #[account(seeds = [VAULT_AUTHORITY_SEED, lock_pool_account.key().as_ref()], bump)]
+    pub pda: AccountInfo<'info>, // Program Derived Address (authority of LockPoolTokenAccount)
+    #[account(
+        init,
+        payer = admin_wallet,
+        seeds = [VAULT_SEED, lock_pool_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pda,
+    )]
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // Shared lock vault (PDA-owned)
+    #[account(
+        init,
+        payer = admin_wallet,
+        seeds = [SALE_POOL_SEED, lock_pool_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pda,
+    )]
+    pub token_pool_account: Account<'info, TokenAccount>, // Sale pool supplying `purchase_tokens` (PDA-owned)
+    #[account(mut)]
+    pub admin_wallet: Signer<'info>, // Presale Manager (pool admin, pays rent)
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePoolHolders<'info> {
+    /// CHECK: Legacy LockPoolState; owner checked here, discriminator and layout checked by `read_legacy_pool`
+    #[account(owner = crate::ID @ CustomError::InvalidLegacyLayout)]
+    pub lock_pool_account: UncheckedAccount<'info>, // Pool whose holders get UserLockInfo records
+    #[account(
+        init_if_needed,
+        payer = upgrade_authority,
+        space = 8 + PoolMigration::INIT_SPACE,
+        seeds = [POOL_MIGRATION_SEED, lock_pool_account.key().as_ref()],
+        bump,
+    )]
+    pub pool_migration: Account<'info, PoolMigration>, // Batch cursor, closed by `migrate_pool`
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ CustomError::Unauthorized)]
+    pub program: Program<'info, crate::program::Hotwings>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key()) @ CustomError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(mut)]
+    pub upgrade_authority: Signer<'info>, // Program upgrade authority (pays rent for the records)
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    /// CHECK: Legacy LockPoolState; owner checked here, discriminator and layout checked by `read_legacy_pool`
+    #[account(mut, owner = crate::ID @ CustomError::InvalidLegacyLayout)]
+    pub lock_pool_account: UncheckedAccount<'info>, // Pool rewritten in the current layout
+    #[account(
+        mut,
+        seeds = [POOL_MIGRATION_SEED, lock_pool_account.key().as_ref()],
+        bump = pool_migration.bump,
+        close = upgrade_authority,
+    )]
+    pub pool_migration: Account<'info, PoolMigration>, // Filled by `migrate_pool_holders`; rent returned here
+    pub mint: Account<'info, Mint>, // Token locked by the pool
+    /// CHECK: Vault authority PDA `[VAULT_AUTHORITY_SEED, lock_pool]`; only its bump is recorded
+    #[account(seeds = [VAULT_AUTHORITY_SEED, lock_pool_account.key().as_ref()], bump)]
+    pub pda: AccountInfo<'info>, // Program Derived Address (authority of LockPoolTokenAccount)
+    #[account(
+        init,
+        payer = upgrade_authority,
+        seeds = [VAULT_SEED, lock_pool_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pda,
+    )]
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // New PDA lock vault, funded before `seal_pool`
+    #[account(
+        init,
+        payer = upgrade_authority,
+        seeds = [SALE_POOL_SEED, lock_pool_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pda,
+    )]
+    pub token_pool_account: Account<'info, TokenAccount>, // New PDA sale pool supplying `purchase_tokens`
+    /// CHECK: Becomes the pool admin (and initial holder of every role); any key may be chosen
+    pub admin_wallet: UncheckedAccount<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ CustomError::Unauthorized)]
+    pub program: Program<'info, crate::program::Hotwings>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key()) @ CustomError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(mut)]
+    pub upgrade_authority: Signer<'info>, // Program upgrade authority (pays rent for the migration)
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    #[account(
+        mut,
+        has_one = pauser @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Pool being paused
+    pub pauser: Signer<'info>, // Emergency pause key
+}
+
+#[derive(Accounts)]
+pub struct QueueChange<'info> {
+    #[account(
+        mut,
+        has_one = admin_wallet @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Pool the change applies to
+    #[account(
+        init,
+        payer = admin_wallet,
+        space = 8 + QueuedChange::INIT_SPACE,
+        seeds = [QUEUED_CHANGE_SEED, lock_pool_account.key().as_ref(), &lock_pool_account.queued_change_count.to_le_bytes()],
+        bump,
+    )]
+    pub queued_change: Account<'info, QueuedChange>, // New timelocked change
+    #[account(mut)]
+    pub admin_wallet: Signer<'info>, // Presale Manager (pays rent)
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteChange<'info> {
+    #[account(mut)]
+    pub lock_pool_account: Account<'info, LockPool>, // Pool the change applies to
+    #[account(
+        mut,
+        seeds = [POOL_CONFIG_SEED, lock_pool_account.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Option<Account<'info, PoolConfig>>, // Pool rules (required only by `ConfigChange::PoolConfig`)
+    #[account(
+        mut,
+        constraint = queued_change.lock_pool == lock_pool_account.key() @ CustomError::ChangeNotQueued,
+    )]
+    pub queued_change: Account<'info, QueuedChange>, // Change whose `eta` has passed
+}
+
+#[derive(Accounts)]
+pub struct CancelChange<'info> {
+    #[account(
+        has_one = admin_wallet @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Pool the change applies to
+    #[account(
+        mut,
+        constraint = queued_change.lock_pool == lock_pool_account.key() @ CustomError::ChangeNotQueued,
+    )]
+    pub queued_change: Account<'info, QueuedChange>, // Change being withdrawn
+    pub admin_wallet: Signer<'info>, // Presale Manager
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        has_one = pending_admin_wallet @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Pool changing hands
+    pub pending_admin_wallet: Signer<'info>, // Proposed admin accepting the role
+}
+
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(
+        mut,
+        has_one = admin_wallet @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Pool governed by the multisig
+    #[account(
+        init,
+        payer = admin_wallet,
+        space = 8 + Multisig::INIT_SPACE,
+        seeds = [MULTISIG_SEED, lock_pool_account.key().as_ref()],
+        bump,
+    )]
+    pub multisig: Account<'info, Multisig>, // M-of-N signer set
+    #[account(mut)]
+    pub admin_wallet: Signer<'info>, // Presale Manager (pays rent)
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>, // Signer set voting on the proposal
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::INIT_SPACE,
+        seeds = [PROPOSAL_SEED, multisig.key().as_ref(), &multisig.proposal_count.to_le_bytes()],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>, // New proposal
+    #[account(mut)]
+    pub proposer: Signer<'info>, // Multisig signer (pays rent)
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    pub multisig: Account<'info, Multisig>, // Signer set voting on the proposal
+    #[account(mut, has_one = multisig @ CustomError::InvalidMultisig)]
+    pub proposal: Account<'info, Proposal>, // Proposal being approved
+    pub signer: Signer<'info>, // Multisig signer
+}
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    pub multisig: Account<'info, Multisig>, // Signer set owning the proposal
+    #[account(mut, has_one = multisig @ CustomError::InvalidMultisig)]
+    pub proposal: Account<'info, Proposal>, // Proposal being cancelled or expired
+    pub signer: Signer<'info>, // Multisig signer (anyone once expired)
+}
+
+#[derive(Accounts)]
+pub struct SealPool<'info> {
+    #[account(
+        mut,
+        has_one = lock_pool_token_account @ CustomError::InvalidVault,
+        has_one = admin_wallet @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Pool being sealed
+    #[account(
+        seeds = [POOL_CONFIG_SEED, lock_pool_account.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Account<'info, PoolConfig>, // Pool rules; can only be created while Loading, so must exist first
+    #[account(token::authority = pda)]
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // Shared lock vault (must back total_locked)
+    /// CHECK: Vault authority PDA `[VAULT_AUTHORITY_SEED, lock_pool]`; owns the lock vault and signs its transfers
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, lock_pool_account.key().as_ref()],
+        bump = lock_pool_account.vault_authority_bump,
+    )]
+    pub pda: AccountInfo<'info>, // Program Derived Address (authority of LockPoolTokenAccount)
+    pub admin_wallet: Signer<'info>, // Presale Manager
+}
+
+#[derive(Accounts)]
+pub struct InitializePoolConfig<'info> {
+    #[account(
+        has_one = admin_wallet @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Pool the rules apply to
+    #[account(
+        init,
+        payer = admin_wallet,
+        space = 8 + PoolConfig::INIT_SPACE,
+        seeds = [POOL_CONFIG_SEED, lock_pool_account.key().as_ref()],
+        bump,
+    )]
+    pub pool_config: Account<'info, PoolConfig>, // Per-pool business rules
+    #[account(mut)]
+    pub admin_wallet: Signer<'info>, // Presale Manager paying for the config account
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeLockAccounts<'info> {
+    #[account(
+        mut,
+        has_one = lock_pool_token_account @ CustomError::InvalidVault,
+        has_one = admin_wallet @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Track all locking data for users
+    #[account(mut, token::authority = pda)]
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // Shared lock vault (PDA-owned)
+    #[account(mut)]
+    pub source_wallet: Account<'info, TokenAccount>, // Admin's funding source wallet
+    #[account(mut)]
+    pub admin_wallet: Signer<'info>, // Wallet signing token transfers (Presale Manager)
+    /// CHECK: Vault authority PDA `[VAULT_AUTHORITY_SEED, lock_pool]`; owns the lock vault and signs its transfers
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, lock_pool_account.key().as_ref()],
+        bump = lock_pool_account.vault_authority_bump,
+    )]
+    pub pda: AccountInfo<'info>, // Program Derived Address (authority of LockPoolTokenAccount)
+    pub token_program: Program<'info, Token>, // Standard SPL Token program
+    pub system_program: Program<'info, System>, // Creates the per-investor UserLockInfo PDAs
+    pub clock: Sysvar<'info, Clock>, // Fetch cluster time from SysvarClock
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize, Debug)]
+pub struct InvestorInfo {
+    pub wallet_address: Pubkey,
+    pub token_amount: u64,
+    pub category: InvestorCategory, // Selects the vesting schedule
+}
+
+// Allocation type of an investor; each has its own VestingSchedule in the PoolConfig
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum InvestorCategory {
+    Presale,
+    Team,
+    Advisors,
+    Marketing,
+}
+
+// Unlock curve of one investor category
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, InitSpace)]
+pub struct VestingSchedule {
+    pub milestone_percentages: [u8; MILESTONE_COUNT], // Cumulative unlock percentage at each milestone
+    pub cliff: i64,                                   // Seconds after `start_time` before linear vesting starts paying
+    pub duration: i64,                                // Seconds after `start_time` until fully vested (0 = no linear vesting)
+}
+
+// Implements the account traits by hand (instead of `#[account]`) so every context that loads the pool
+// rejects an unknown layout version in `try_deserialize`. Named apart from the legacy `LockPoolState`
+// so the two never share a discriminator.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct LockPool {
+    pub version: u8,                     // Account layout version (LOCK_POOL_VERSION)
+    pub phase: PoolPhase,                // Loading (investors appendable) or Sealed
+    pub total_locked: u64,               // Total locked tokens in the pool
+    pub holder_count: u64,               // Number of UserLockInfo records created for this pool
+    pub start_time: i64,  
+    pub current_milestone: u8, 
+    pub full_unlock_executed: bool,
+    pub is_max_hold_limit_active: bool,  // NEW: Enable/Disable max hold restrictions
+    pub unlock_in_progress: bool,        // A batched unlock job is open
+    pub pending_milestone: u8,           // Milestone committed when the open job completes
+    pub pending_market_cap: u64,         // Market cap that opened the pending milestone job
+    pub pending_full_unlock: bool,       // The open job is the full unlock
+    pub unlock_cursor: u64,              // Index of the next UserLockInfo to process in the open job
+    pub milestone_observed_at: [i64; MILESTONE_COUNT], // First time each milestone was observed (0 = below threshold)
+    pub allocation_root: [u8; 32],       // Merkle root of (wallet, amount, category) allocations (zero = none)
+    pub unregistered_allocation: u64,    // Funded allocation tokens not yet proven into a UserLockInfo
+    pub vault_authority_bump: u8,        // Bump of the vault authority PDA `[VAULT_AUTHORITY_SEED, lock_pool]`
+    pub mint: Pubkey,                    // Token mint locked by the pool
+    pub admin_wallet: Pubkey,            // Presale Manager that created the pool
+    pub lock_pool_token_account: Pubkey, // Lock vault `[VAULT_SEED, lock_pool]`
+    pub token_pool_account: Pubkey,      // Sale pool `[SALE_POOL_SEED, lock_pool]` supplying `purchase_tokens`
+    pub bump: u8,                        // Bump of the pool PDA `[LOCK_POOL_SEED, mint]`
+    pub dust_reclaimed: u64,             // Vault surplus swept by `reclaim_dust` so far
+    pub pending_admin_wallet: Pubkey,    // Admin proposed by a queued `ConfigChange::Admin` (default = none)
+    pub milestone_keeper: Pubkey,        // Cranks market-cap checks, full unlock and unlock batches
+    pub treasury: Pubkey,                // Moves project funds (auto-sell, dust sweeps, sale approvals)
+    pub pauser: Pubkey,                  // Emergency pause key
+    pub multisig: Pubkey,                // Multisig approving gated instructions (default = none)
+    pub timelock_delay: i64,             // Seconds a queued configuration or admin change waits before execution
+    pub queued_change_count: u64,        // Index of the next QueuedChange
+    pub paused_flags: u8,                // PAUSE_* bits of the scopes currently halted
+    pub max_holders: u64,                // Hard cap on `holder_count` (raised through `ConfigChange::MaxHolders`)
+}
+
+impl anchor_lang::Discriminator for LockPool {
+    const DISCRIMINATOR: [u8; 8] = [57, 9, 171, 89, 205, 211, 78, 84]; // sha256("account:LockPool")[..8], as `#[account]` derives it
+}
+
+impl anchor_lang::Owner for LockPool {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+impl anchor_lang::AccountSerialize for LockPool {
+    fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<()> {
+        writer
+            .write_all(&<Self as anchor_lang::Discriminator>::DISCRIMINATOR)
+            .map_err(|_| error!(ErrorCode::AccountDidNotSerialize))?;
+        AnchorSerialize::serialize(self, writer).map_err(|_| error!(ErrorCode::AccountDidNotSerialize))
+    }
+}
+
+impl anchor_lang::AccountDeserialize for LockPool {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        require!(buf.len() >= 8, ErrorCode::AccountDiscriminatorNotFound);
+        require!(
+            buf[..8] == <Self as anchor_lang::Discriminator>::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        // ✅ Security Check: Only the current layout is readable; older pools go through `migrate_pool` first
+        require!(buf.get(8) == Some(&LOCK_POOL_VERSION), CustomError::UnsupportedVersion);
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        let mut data: &[u8] = &buf[8..];
+        AnchorDeserialize::deserialize(&mut data).map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+    }
+}
+
+// Layouts of the unversioned `LockPoolState` account that `migrate_pool` converts into a LockPool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum LegacyLayout {
+    Original,       // `{ total_locked, users }` (states.rs)
+    WithMilestones, // Original plus start_time, current_milestone, full_unlock_executed, is_max_hold_limit_active
+}
+
+// Per-user entry of the legacy `users` vector
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+struct LegacyUserLockInfo {
+    user_wallet: Pubkey,
+    total_tokens: u64,
+    unlocked_tokens: u64,
+    locked_tokens: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct LegacyLockPoolOriginal {
+    total_locked: u64,
+    users: Vec<LegacyUserLockInfo>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct LegacyLockPoolWithMilestones {
+    total_locked: u64,
+    users: Vec<LegacyUserLockInfo>,
+    start_time: i64,
+    current_milestone: u8,
+    full_unlock_executed: bool,
+    is_max_hold_limit_active: bool,
+}
+
+impl From<LegacyLockPoolOriginal> for LegacyLockPoolWithMilestones {
+    fn from(pool: LegacyLockPoolOriginal) -> Self {
+        Self {
+            total_locked: pool.total_locked,
+            users: pool.users,
+            start_time: 0,
+            current_milestone: 0,
+            full_unlock_executed: false,
+            is_max_hold_limit_active: true,
+        }
+    }
+}
+
+/// Progress of a batched legacy migration, stored at PDA `[POOL_MIGRATION_SEED, lock_pool]`; closed by `migrate_pool`.
+#[account]
+#[derive(InitSpace)]
+pub struct PoolMigration {
+    pub lock_pool: Pubkey,              // Legacy pool being migrated
+    pub legacy_layout: LegacyLayout,    // Layout every holder batch was decoded with
+    pub holder_cursor: u64,             // Index of the next merged legacy holder without a UserLockInfo
+    pub total_locked: u64,              // Locked tokens of the records created so far
+    pub bump: u8,
+}
+
+impl PoolMigration {
+    // Accounts for the next recreated legacy holder; returns the index its UserLockInfo takes
+    fn record_holder(&mut self, holder: &LegacyUserLockInfo) -> Result<u64> {
+        let index = self.holder_cursor;
+        self.holder_cursor = index.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        self.total_locked = self.total_locked.checked_add(holder.locked_tokens).ok_or(CustomError::MathOverflow)?;
+        Ok(index)
+    }
+}
+
+impl LockPool {
+    // Reserves the next UserLockInfo index; fails with `PoolFull` once `max_holders` records exist
+    fn register_holder(&mut self) -> Result<u64> {
+        let index = self.holder_count;
+        require!(index < self.max_holders, CustomError::PoolFull);
+        self.holder_count = index.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        Ok(index)
+    }
+
+    // Fails with `Paused` while any bit of `flag` is set
+    fn require_not_paused(&self, flag: u8) -> Result<()> {
+        require!(self.paused_flags & flag == 0, CustomError::Paused);
+        Ok(())
+    }
+
+    // Tokens entering the vault on behalf of a holder
+    fn add_locked(&mut self, amount: u64) -> Result<()> {
+        self.total_locked = self.total_locked.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Tokens leaving the vault to a holder
+    fn release(&mut self, amount: u64) -> Result<()> {
+        self.total_locked = self.total_locked.checked_sub(amount).ok_or(CustomError::UnlockUnderflow)?;
+        Ok(())
+    }
+}
+
+// Lifecycle of a lock pool: investors are appended while Loading; `seal_pool` fixes `start_time`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum PoolPhase {
+    Loading,
+    Sealed,
+}
+
+/// Business rules of a lock pool, stored at PDA `[POOL_CONFIG_SEED, lock_pool]`.
+#[account]
+#[derive(InitSpace)]
+pub struct PoolConfig {
+    pub lock_pool: Pubkey,                                 // LockPool these rules apply to
+    pub milestone_thresholds: [u64; MILESTONE_COUNT],      // Market cap required for each milestone (strictly increasing)
+    pub schedules: [VestingSchedule; CATEGORY_COUNT],      // Vesting schedule per InvestorCategory
+    pub tax_bps: u16,                                      // DEX transfer tax in basis points (150 = 1.5%)
+    pub burn_share_bps: u16,                               // Share of the tax that is burned (5_000 = half)
+    pub max_hold_amount: u64,                              // Max tokens a wallet may hold while the limit is active
+    pub full_unlock_delay: i64,                            // Seconds after `start_time` before the full unlock
+    pub auto_sell_bps: u16,                                // Share of the project wallet auto-sold on finalize (2_500 = 25%)
+    pub price_feed: Pubkey,                                // Pyth PriceUpdateV2 account (or ManualPriceFeed on `localnet` builds) driving market cap
+    pub price_feed_id: [u8; 32],                           // Pyth feed id the PriceUpdateV2 account must carry
+    pub max_price_age: i64,                                // Max seconds since the price was published
+    pub max_confidence_bps: u16,                           // Max confidence interval relative to price
+    pub milestone_hold_secs: i64,                          // Seconds a milestone must stay reached before it is committed
+    pub amm_pool: Pubkey,                                  // Raydium CPMM or Orca Whirlpool pool driving market cap (default = unused)
+    pub bump: u8,                                          // Bump of the config PDA
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize, Debug, InitSpace)]
+pub struct PoolConfigParams {
+    pub milestone_thresholds: [u64; MILESTONE_COUNT],
+    pub schedules: [VestingSchedule; CATEGORY_COUNT],
+    pub tax_bps: u16,
+    pub burn_share_bps: u16,
+    pub max_hold_amount: u64,
+    pub full_unlock_delay: i64,
+    pub auto_sell_bps: u16,
+    pub price_feed: Pubkey,
+    pub price_feed_id: [u8; 32],
+    pub max_price_age: i64,
+    pub max_confidence_bps: u16,
+    pub milestone_hold_secs: i64,
+    pub amm_pool: Pubkey,
+}
+
+impl PoolConfig {
+    // Copies the rule fields of `params`
+    fn apply(&mut self, params: &PoolConfigParams) {
+        self.milestone_thresholds = params.milestone_thresholds;
+        self.schedules = params.schedules;
+        self.tax_bps = params.tax_bps;
+        self.burn_share_bps = params.burn_share_bps;
+        self.max_hold_amount = params.max_hold_amount;
+        self.full_unlock_delay = params.full_unlock_delay;
+        self.auto_sell_bps = params.auto_sell_bps;
+        self.price_feed = params.price_feed;
+        self.price_feed_id = params.price_feed_id;
+        self.max_price_age = params.max_price_age;
+        self.max_confidence_bps = params.max_confidence_bps;
+        self.milestone_hold_secs = params.milestone_hold_secs;
+        self.amm_pool = params.amm_pool;
+    }
+
+    pub fn schedule(&self, category: InvestorCategory) -> &VestingSchedule {
+        &self.schedules[category as usize]
+    }
+}
+
+/// M-of-N signer set of a pool, stored at PDA `[MULTISIG_SEED, lock_pool]`.
+#[account]
+#[derive(InitSpace)]
+pub struct Multisig {
+    pub lock_pool: Pubkey,              // Pool governed by this multisig
+    #[max_len(MAX_MULTISIG_SIGNERS)]
+    pub signers: Vec<Pubkey>,           // Distinct signer keys (bit i of `Proposal.approvals`)
+    pub threshold: u8,                  // Approvals required to execute a proposal
+    pub proposal_count: u64,            // Index of the next proposal
+    pub bump: u8,
+}
+
+/// Multisig proposal authorizing one gated instruction, stored at PDA `[PROPOSAL_SEED, multisig, index]`.
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    pub multisig: Pubkey,               // Multisig voting on the proposal
+    pub index: u64,                     // Position in the multisig's proposal sequence
+    pub proposer: Pubkey,
+    pub action: ProposalAction,         // Instruction (and parameters) authorized
+    pub approvals: u16,                 // Bitmap of approving signer indices
+    pub threshold: u8,                  // Multisig threshold when the proposal was created
+    pub created_at: i64,
+    pub expires_at: i64,                // No approvals or execution from this time on
+    pub status: ProposalStatus,
+    pub bump: u8,
+}
+
+// Gated instructions a proposal can authorize
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum ProposalAction {
+    UnlockTokens,
+    UnlockTokensFromAmm,
+    FullUnlock,
+    FinalizeUnlock { dex_liquidity_wallet: Pubkey },
+    ReclaimDust { destination: Pubkey },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum ProposalStatus {
+    Active,
+    Executed,
+    Cancelled,
+    Expired,
+}
+
+/// Timelocked configuration or admin change, stored at PDA `[QUEUED_CHANGE_SEED, lock_pool, index]`.
+#[account]
+#[derive(InitSpace)]
+pub struct QueuedChange {
+    pub lock_pool: Pubkey,              // Pool the change applies to
+    pub index: u64,                     // Position in the pool's change queue
+    pub change: ConfigChange,
+    pub queued_at: i64,
+    pub eta: i64,                       // Earliest execution time (`queued_at + timelock_delay`)
+    pub status: QueuedChangeStatus,
+    pub bump: u8,
+}
+
+// Admin-controlled settings that only change through the timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub enum ConfigChange {
+    PoolConfig(PoolConfigParams),
+    Admin { new_admin: Pubkey },
+    Roles { milestone_keeper: Pubkey, treasury: Pubkey, pauser: Pubkey },
+    TimelockDelay { delay: i64 },
+    Unpause { flags: u8 }, // PAUSE_* bits to clear
+    MaxHolders { max_holders: u64 }, // New hard cap on UserLockInfo records
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum QueuedChangeStatus {
+    Queued,
+    Executed,
+    Cancelled,
+}
+
+/// Program-owned price account used in place of Pyth on local clusters (`localnet` builds only).
+#[account]
+#[derive(InitSpace)]
+pub struct ManualPriceFeed {
+    pub authority: Pubkey,              // Wallet allowed to publish prices
+    pub price: i64,                     // Price in quote units scaled by 10^expo
+    pub conf: u64,                      // Confidence interval, same scale as `price`
+    pub expo: i32,                      // Price exponent
+    pub publish_time: i64,              // Unix time of the last update
+    pub bump: u8,                       // Bump of the feed PDA
+}
+
+/// Per-investor lock record, stored at PDA `[USER_LOCK_SEED, lock_pool, user_wallet]`.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct UserLockInfo {
+    pub lock_pool: Pubkey,              // LockPool this record belongs to
+    pub index: u64,                     // Position of the record in batched unlock order
+    pub user_wallet: Pubkey,            // Wallet address of the user
+    pub category: InvestorCategory,     // Selects the vesting schedule
+    pub total_tokens: u64,              // Purchased tokens during presale
+    pub unlocked_tokens: u64,           // Unlocked tokens (via milestones)
+    pub locked_tokens: u64,             // Remaining locked tokens
+    pub allocation_registered: bool,    // Merkle allocation leaf already added to this record
+    pub bump: u8,                       // Bump of the record PDA
+}
+
+impl UserLockInfo {
+    // Adds newly locked tokens to the record
+    fn add_locked(&mut self, amount: u64) -> Result<()> {
+        self.total_tokens = self.total_tokens.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+        self.locked_tokens = self.locked_tokens.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Moves tokens from locked to unlocked as they are paid out
+    fn release(&mut self, amount: u64) -> Result<()> {
+        self.locked_tokens = self.locked_tokens.checked_sub(amount).ok_or(CustomError::UnlockUnderflow)?;
+        self.unlocked_tokens = self.unlocked_tokens.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Books a purchase in full; the share already paid out to the buyer counts as unlocked, so claims only pay the rest
+    fn add_purchase(&mut self, total_paid_tokens: u64, unlocked_tokens: u64) -> Result<()> {
+        self.add_locked(total_paid_tokens)?;
+        self.release(unlocked_tokens)
+    }
+}
+
+#[derive(Accounts)]
+pub struct UnlockTokens<'info> {
+    #[account(
+        mut,
+        has_one = lock_pool_token_account @ CustomError::InvalidVault,
+        has_one = mint @ CustomError::InvalidMint,
+        has_one = milestone_keeper @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Global LockPool (tracks locking state across users)
+    #[account(
+        seeds = [POOL_CONFIG_SEED, lock_pool_account.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Account<'info, PoolConfig>, // Pool rules (milestones, taxes, limits)
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // Lock vault (excluded from circulating supply)
+    pub mint: Account<'info, Mint>, // Token mint (total supply and decimals)
+    /// CHECK: Parsed by `load_price`; must be the feed recorded in the pool config
+    #[account(constraint = price_feed.key() == pool_config.price_feed @ CustomError::InvalidPriceFeed)]
+    pub price_feed: AccountInfo<'info>, // Pyth PriceUpdateV2 account (program-owned ManualPriceFeed on `localnet` builds)
+    pub milestone_keeper: Signer<'info>, // Milestone keeper triggering the unlocking process
+    #[account(mut)]
+    pub proposal: Option<Account<'info, Proposal>>, // Approved multisig proposal (required when the call commits a milestone)
+}
+
+#[derive(Accounts)]
+pub struct UnlockTokensFromAmm<'info> {
+    #[account(
+        mut,
+        has_one = lock_pool_token_account @ CustomError::InvalidVault,
+        has_one = mint @ CustomError::InvalidMint,
+        has_one = milestone_keeper @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Global LockPool (tracks locking state across users)
+    #[account(
+        seeds = [POOL_CONFIG_SEED, lock_pool_account.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Account<'info, PoolConfig>, // Pool rules (milestones, taxes, limits)
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // Lock vault (excluded from circulating supply)
+    pub mint: Account<'info, Mint>, // Token mint (total supply)
+    pub quote_mint: Account<'info, Mint>, // Quote mint of the AMM pool (e.g. USDC)
+    /// CHECK: Parsed by `load_amm_spot_price`; must be the pool recorded in the pool config
+    #[account(constraint = amm_pool.key() == pool_config.amm_pool @ CustomError::InvalidAmmPool)]
+    pub amm_pool: AccountInfo<'info>, // Raydium CPMM PoolState or Orca Whirlpool
+    pub token_vault: Account<'info, TokenAccount>, // AMM vault holding the pool token
+    pub quote_vault: Account<'info, TokenAccount>, // AMM vault holding the quote token
+    pub milestone_keeper: Signer<'info>, // Milestone keeper triggering the unlocking process
+    #[account(mut)]
+    pub proposal: Option<Account<'info, Proposal>>, // Approved multisig proposal (required when the call commits a milestone)
+}
+
+#[derive(Accounts)]
+pub struct InitializeManualPriceFeed<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ManualPriceFeed::INIT_SPACE,
+        seeds = [MANUAL_PRICE_FEED_SEED, authority.key().as_ref()],
+        bump,
+    )]
+    pub price_feed: Account<'info, ManualPriceFeed>, // Program-owned price account for local testing
+    #[account(mut)]
+    pub authority: Signer<'info>, // Wallet allowed to publish prices
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateManualPrice<'info> {
+    #[account(mut, has_one = authority)]
+    pub price_feed: Account<'info, ManualPriceFeed>, // Program-owned price account
+    pub authority: Signer<'info>, // Wallet allowed to publish prices
+}
+
+#[derive(Accounts)]
+pub struct FullUnlock<'info> {
+    #[account(
+        mut,
+        has_one = milestone_keeper @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Global LockPool (tracks locking state across users)
+    #[account(
+        seeds = [POOL_CONFIG_SEED, lock_pool_account.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Account<'info, PoolConfig>, // Pool rules (milestones, taxes, limits)
+    pub milestone_keeper: Signer<'info>, // Milestone keeper triggering the full unlock operation
+    #[account(mut)]
+    pub proposal: Option<Account<'info, Proposal>>, // Approved multisig proposal (required once the pool has a multisig)
+    pub clock: Sysvar<'info, Clock>, // Solana Clock Sysvar to fetch current cluster time
+}
+
+#[derive(Accounts)]
+pub struct ProcessUnlockBatch<'info> {
+    #[account(
+        mut,
+        has_one = lock_pool_token_account @ CustomError::InvalidVault,
+        has_one = mint @ CustomError::InvalidMint,
+        has_one = milestone_keeper @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Global LockPool (holds the unlock cursor)
+    #[account(
+        seeds = [POOL_CONFIG_SEED, lock_pool_account.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Account<'info, PoolConfig>, // Pool rules (milestones, taxes, limits)
+    #[account(mut, token::authority = pda)]
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // PDA-controlled SPL token account (the lock pool)
+    /// CHECK: Vault authority PDA `[VAULT_AUTHORITY_SEED, lock_pool]`; owns the lock vault and signs its transfers
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, lock_pool_account.key().as_ref()],
+        bump = lock_pool_account.vault_authority_bump,
+    )]
+    pub pda: AccountInfo<'info>, // Program Derived Address (authority of LockPoolTokenAccount)
+    pub mint: Account<'info, Mint>, // Pool mint (recipients' associated token accounts)
+    #[account(mut)]
+    pub milestone_keeper: Signer<'info>, // Milestone keeper cranking the distribution job (pays for missing token accounts)
+    pub token_program: Program<'info, Token>, // SPL Token program for token transfers
+    pub associated_token_program: Program<'info, AssociatedToken>, // Creates missing recipient token accounts
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AbortUnlockJob<'info> {
+    #[account(
+        mut,
+        has_one = milestone_keeper @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Global LockPool (holds the unlock cursor)
+    pub milestone_keeper: Signer<'info>, // Milestone keeper rolling back the open job
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(
+        mut,
+        has_one = lock_pool_token_account @ CustomError::InvalidVault,
+        has_one = mint @ CustomError::InvalidMint,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Global LockPool (current milestone, total locked)
+    #[account(
+        seeds = [POOL_CONFIG_SEED, lock_pool_account.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Account<'info, PoolConfig>, // Pool rules (milestones, taxes, limits)
+    #[account(
+        mut,
+        seeds = [USER_LOCK_SEED, lock_pool_account.key().as_ref(), user_wallet.key().as_ref()],
+        bump = user_lock_account.bump,
+    )]
+    pub user_lock_account: Account<'info, UserLockInfo>, // Investor's per-wallet lock record
+    #[account(mut, token::authority = pda)]
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // PDA-controlled SPL token account (the lock pool)
+    /// CHECK: Vault authority PDA `[VAULT_AUTHORITY_SEED, lock_pool]`; owns the lock vault and signs its transfers
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, lock_pool_account.key().as_ref()],
+        bump = lock_pool_account.vault_authority_bump,
+    )]
+    pub pda: AccountInfo<'info>, // Program Derived Address (authority of LockPoolTokenAccount)
+    pub mint: Account<'info, Mint>, // Pool mint
+    #[account(mut)]
+    pub user_wallet: Signer<'info>, // Investor claiming their unlocked tokens (pays for a missing token account)
+    #[account(
+        init_if_needed,
+        payer = user_wallet,
+        associated_token::mint = mint,
+        associated_token::authority = user_wallet,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>, // Investor's associated token account receiving the claim
+    pub token_program: Program<'info, Token>, // SPL Token program for token transfers
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitAllocationRoot<'info> {
+    #[account(
+        mut,
+        has_one = lock_pool_token_account @ CustomError::InvalidVault,
+        has_one = admin_wallet @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Track all locking data for users
+    #[account(mut, token::authority = pda)]
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // Shared lock vault (PDA-owned)
+    #[account(mut)]
+    pub source_wallet: Account<'info, TokenAccount>, // Admin's funding source wallet
+    #[account(mut)]
+    pub admin_wallet: Signer<'info>, // Wallet signing token transfers (Presale Manager)
+    /// CHECK: Vault authority PDA `[VAULT_AUTHORITY_SEED, lock_pool]`; owns the lock vault and signs its transfers
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, lock_pool_account.key().as_ref()],
+        bump = lock_pool_account.vault_authority_bump,
+    )]
+    pub pda: AccountInfo<'info>, // Program Derived Address (authority of LockPoolTokenAccount)
+    pub token_program: Program<'info, Token>, // Standard SPL Token program
+}
+
+#[derive(Accounts)]
+pub struct ClaimWithProof<'info> {
+    #[account(
+        mut,
+        has_one = lock_pool_token_account @ CustomError::InvalidVault,
+        has_one = mint @ CustomError::InvalidMint,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Global LockPool (allocation root)
+    #[account(
+        seeds = [POOL_CONFIG_SEED, lock_pool_account.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Account<'info, PoolConfig>, // Pool rules (milestones, taxes, limits)
+    #[account(
+        init_if_needed,
+        payer = user_wallet,
+        space = 8 + UserLockInfo::INIT_SPACE,
+        seeds = [USER_LOCK_SEED, lock_pool_account.key().as_ref(), user_wallet.key().as_ref()],
+        bump,
+    )]
+    pub user_lock_account: Account<'info, UserLockInfo>, // Investor's per-wallet lock record
+    #[account(mut, token::authority = pda)]
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // PDA-controlled SPL token account (the lock pool)
+    /// CHECK: Vault authority PDA `[VAULT_AUTHORITY_SEED, lock_pool]`; owns the lock vault and signs its transfers
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, lock_pool_account.key().as_ref()],
+        bump = lock_pool_account.vault_authority_bump,
+    )]
+    pub pda: AccountInfo<'info>, // Program Derived Address (authority of LockPoolTokenAccount)
+    pub mint: Account<'info, Mint>, // Pool mint
+    #[account(mut)]
+    pub user_wallet: Signer<'info>, // Investor proving their allocation (pays for the record)
+    #[account(
+        init_if_needed,
+        payer = user_wallet,
+        associated_token::mint = mint,
+        associated_token::authority = user_wallet,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>, // Investor's associated token account receiving the claim
+    pub token_program: Program<'info, Token>, // SPL Token program for token transfers
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyPool<'info> {
+    #[account(
+        has_one = lock_pool_token_account @ CustomError::InvalidVault,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Pool being audited
+    #[account(
+        init_if_needed,
+        payer = auditor,
+        space = 8 + PoolAudit::INIT_SPACE,
+        seeds = [POOL_AUDIT_SEED, lock_pool_account.key().as_ref(), auditor.key().as_ref()],
+        bump,
+    )]
+    pub pool_audit: Account<'info, PoolAudit>, // The auditor's cursor and running sum
+    #[account(token::authority = pda)]
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // Shared lock vault (must back total_locked)
+    /// CHECK: Vault authority PDA `[VAULT_AUTHORITY_SEED, lock_pool]`; owns the lock vault and signs its transfers
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, lock_pool_account.key().as_ref()],
+        bump = lock_pool_account.vault_authority_bump,
+    )]
+    pub pda: AccountInfo<'info>, // Program Derived Address (authority of LockPoolTokenAccount)
+    #[account(mut)]
+    pub auditor: Signer<'info>, // Anyone; owns (and pays rent for) its own PoolAudit, so audits cannot reset each other
+    pub system_program: Program<'info, System>,
+}
+
+/// Progress of one auditor's `verify_pool` run, stored at PDA `[POOL_AUDIT_SEED, lock_pool, auditor]`.
+#[account]
+#[derive(InitSpace)]
+pub struct PoolAudit {
+    pub lock_pool: Pubkey,              // Pool being audited
+    pub cursor: u64,                    // Index of the next UserLockInfo to sum
+    pub user_locked_sum: u64,           // Sum of UserLockInfo.locked_tokens over records `[0, cursor)`
+    pub total_locked: u64,              // LockPool.total_locked when the audit started
+    pub holder_count: u64,              // LockPool.holder_count when the audit started
+    pub unregistered_allocation: u64,   // LockPool.unregistered_allocation when the audit started
+    pub bump: u8,
+}
+
+impl PoolAudit {
+    // Starts over from record 0 against the pool's current totals
+    fn restart(&mut self, lock_pool_key: Pubkey, lock_pool: &LockPool, bump: u8) {
+        self.lock_pool = lock_pool_key;
+        self.cursor = 0;
+        self.user_locked_sum = 0;
+        self.total_locked = lock_pool.total_locked;
+        self.holder_count = lock_pool.holder_count;
+        self.unregistered_allocation = lock_pool.unregistered_allocation;
+        self.bump = bump;
+    }
+
+    // Whether the pool still has the totals the audit started from (every lock, unlock or claim changes them)
+    fn matches(&self, lock_pool: &LockPool) -> bool {
+        self.total_locked == lock_pool.total_locked
+            && self.holder_count == lock_pool.holder_count
+            && self.unregistered_allocation == lock_pool.unregistered_allocation
+    }
+}
+
+// Return data of `verify_pool`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PoolTotals {
+    pub total_locked: u64,            // LockPool.total_locked
+    pub user_locked_sum: u64,         // Sum of UserLockInfo.locked_tokens over records `[0, verified_holders)`
+    pub unregistered_allocation: u64, // Committed allocations not yet claimed with a proof
+    pub vault_balance: u64,           // Lock vault token balance
+    pub holder_count: u64,            // LockPool.holder_count
+    pub verified_holders: u64,        // Records summed so far; the invariants were checked once it equals `holder_count`
+}
+
+#[derive(Accounts)]
+pub struct ReclaimDust<'info> {
+    #[account(
+        mut,
+        has_one = lock_pool_token_account @ CustomError::InvalidVault,
+        has_one = treasury @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Pool whose vault surplus is swept
+    #[account(mut, token::authority = pda)]
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // PDA-controlled SPL token account (the lock pool)
+    /// CHECK: Vault authority PDA `[VAULT_AUTHORITY_SEED, lock_pool]`; owns the lock vault and signs its transfers
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, lock_pool_account.key().as_ref()],
+        bump = lock_pool_account.vault_authority_bump,
+    )]
+    pub pda: AccountInfo<'info>, // Program Derived Address (authority of LockPoolTokenAccount)
+    #[account(mut, token::mint = lock_pool_account.mint)]
+    pub destination: Account<'info, TokenAccount>, // Receives the surplus
+    pub treasury: Signer<'info>, // Treasury operator
+    #[account(mut)]
+    pub proposal: Option<Account<'info, Proposal>>, // Approved multisig proposal (required once the pool has a multisig)
+    pub token_program: Program<'info, Token>, // SPL Token program for token transfers
+}
+
+#[derive(Accounts)]
+pub struct PurchaseTokens<'info> {
+    #[account(
+        mut,
+        has_one = lock_pool_token_account @ CustomError::InvalidVault,
+        has_one = token_pool_account @ CustomError::InvalidVault,
+        has_one = treasury @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Global LockPool
+    #[account(
+        seeds = [POOL_CONFIG_SEED, lock_pool_account.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Account<'info, PoolConfig>, // Pool rules (milestones, taxes, limits)
+    #[account(mut, token::authority = pda)]
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // PDA-controlled lock pool account
+    /// CHECK: Vault authority PDA `[VAULT_AUTHORITY_SEED, lock_pool]`; owns the lock vault and signs its transfers
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED, lock_pool_account.key().as_ref()],
+        bump = lock_pool_account.vault_authority_bump,
+    )]
+    pub pda: AccountInfo<'info>, // Program Derived Address (authority of LockPoolTokenAccount)
+    #[account(mut)]
+    pub user_wallet: Signer<'info>, // Buyer's wallet (receiving unlocked tokens)
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>, // Buyer's token account to receive unlocked tokens
+    #[account(
+        mut,
+        token::mint = lock_pool_account.mint,
+        token::authority = pda,
+        constraint = token_pool_account.key() != lock_pool_token_account.key() @ CustomError::InvalidVault,
+    )]
+    pub token_pool_account: Account<'info, TokenAccount>, // Sale pool supplying the purchased tokens
+    pub treasury: Signer<'info>, // Treasury operator approving the sale once the buyer has paid
+    #[account(
+        init_if_needed,
+        payer = user_wallet,
+        space = 8 + UserLockInfo::INIT_SPACE,
+        seeds = [USER_LOCK_SEED, lock_pool_account.key().as_ref(), user_wallet.key().as_ref()],
+        bump,
+    )]
+    pub user_lock_account: Account<'info, UserLockInfo>, // Buyer's per-wallet lock record
+    pub token_program: Program<'info, Token>, // SPL Token Program
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeUnlock<'info> {
+    #[account(
+        mut,
+        has_one = treasury @ CustomError::Unauthorized,
+    )]
+    pub lock_pool_account: Account<'info, LockPool>, // Global LockPool (tracks locking state across users)
+    #[account(
+        seeds = [POOL_CONFIG_SEED, lock_pool_account.key().as_ref()],
+        bump = pool_config.bump,
+    )]
+    pub pool_config: Account<'info, PoolConfig>, // Pool rules (milestones, taxes, limits)
+    /// CHECK: Project wallet (source of auto-sales)
+    #[account(mut)]
+    pub project_wallet: Account<'info, TokenAccount>, // Project/Presale Manager's wallet
+    /// CHECK: Authority over the `project_wallet`
+    pub project_wallet_authority: Signer<'info>, // Authority to approve sales from the project wallet
+    #[account(mut)]
+    pub dex_liquidity_wallet: Account<'info, TokenAccount>, // Wallet or DEX account receiving the auto-sell tokens
+    pub treasury: Signer<'info>, // Treasury operator approving the auto-sell
+    #[account(mut)]
+    pub proposal: Option<Account<'info, Proposal>>, // Approved multisig proposal (required once the pool has a multisig)
+    pub token_program: Program<'info, Token>, // SPL Token program for transfers
+    pub clock: Sysvar<'info, Clock>, // Solana Clock Sysvar to fetch current cluster time
+}
+
+
+// Rounding policy: every proportional share (milestone percentage, linear vesting, tax, burn share,
+// auto-sell) is floored, so a holder is never paid more than they are owed. The per-user remainder
+// stays in that user's `locked_tokens` and is paid by a later milestone or the full unlock; vault
+// tokens above `total_locked` back no one and can be swept with `reclaim_dust`.
+fn mul_div_floor(value: u64, numerator: u64, denominator: u64) -> Result<u64> {
+    require!(denominator > 0, CustomError::MathOverflow);
+    let result = value as u128 * numerator as u128 / denominator as u128;
+    u64::try_from(result).map_err(|_| error!(CustomError::MathOverflow))
+}
+
+// Highest milestone whose market cap threshold is reached (0 = none)
+fn milestone_from_market_cap(pool_config: &PoolConfig, market_cap: u64) -> u8 {
+    pool_config
+        .milestone_thresholds
+        .iter()
+        .take_while(|threshold| market_cap >= **threshold)
+        .count() as u8
+}
+
+fn milestone_percentage_from_milestone(schedule: &VestingSchedule, current_milestone: u8) -> u8 {
+    match current_milestone as usize {
+        0 => 0, // No milestone reached yet
+        milestone => schedule.milestone_percentages[milestone.min(MILESTONE_COUNT) - 1],
+    }
+}
+
+// Records a market cap observation; returns the higher milestone to commit once it has been sustained
+fn observe_market_cap(
+    lock_pool: &mut LockPool,
+    pool_config: &PoolConfig,
+    market_cap: u64,
+    current_time: i64,
+) -> Option<u8> {
+    // Record when each milestone was first observed; milestones below the market cap reset
+    let reached = milestone_from_market_cap(pool_config, market_cap);
+    record_milestone_observation(lock_pool, reached, current_time);
+
+    // Only a milestone held for the whole window is committed
+    let milestone = sustained_milestone(lock_pool, pool_config, current_time);
+
+    // Nothing to commit yet (or a job is still running): keep the observation
+    (milestone > lock_pool.current_milestone && !lock_pool.unlock_in_progress).then_some(milestone)
+}
+
+// Opens a distribution job for `milestone`; the milestone is committed once every user has been processed
+fn start_milestone_job(
+    lock_pool: &mut Account<LockPool>,
+    pool_config: &PoolConfig,
+    milestone: u8,
+    market_cap: u64,
+    current_time: i64,
+) {
+    let lock_pool_key = lock_pool.key();
+    lock_pool.pending_milestone = milestone;
+    lock_pool.pending_market_cap = market_cap;
+    lock_pool.pending_full_unlock = false;
+    start_unlock_job(lock_pool_key, lock_pool, pool_config, current_time);
+}
+
+// Stamps newly reached milestones with `current_time` and clears those the market cap fell below
+fn record_milestone_observation(lock_pool: &mut LockPool, reached: u8, current_time: i64) {
+    for (i, observed_at) in lock_pool.milestone_observed_at.iter_mut().enumerate() {
+        if i < reached as usize {
+            if *observed_at == 0 {
+                *observed_at = current_time;
+            }
+        } else {
+            *observed_at = 0; // Dipped below: the pending milestone resets
+        }
+    }
+}
+
+// Highest milestone observed continuously for at least `milestone_hold_secs` (0 = none)
+fn sustained_milestone(lock_pool: &LockPool, pool_config: &PoolConfig, current_time: i64) -> u8 {
+    lock_pool
+        .milestone_observed_at
+        .iter()
+        .rposition(|observed_at| {
+            *observed_at != 0 && current_time.saturating_sub(*observed_at) >= pool_config.milestone_hold_secs
+        })
+        .map_or(0, |i| i as u8 + 1)
+}
+
+// Tokens of `user` released so far under its category schedule: the greater of the milestone share and the linear vesting share
+fn vested_amount(
+    pool_config: &PoolConfig,
+    start_time: i64,
+    user: &UserLockInfo,
+    milestone: u8,
+    current_time: i64,
+) -> Result<u64> {
+    let schedule = pool_config.schedule(user.category);
+    let percentage = milestone_percentage_from_milestone(schedule, milestone);
+    let milestone_amount = mul_div_floor(user.total_tokens, percentage as u64, 100)?;
+
+    let elapsed = current_time.checked_sub(start_time).ok_or(CustomError::MathOverflow)?;
+    let time_amount = if schedule.duration == 0 || elapsed < schedule.cliff.max(0) {
+        0 // Linear vesting disabled or still inside the cliff
+    } else if elapsed >= schedule.duration {
+        user.total_tokens
+    } else {
+        mul_div_floor(user.total_tokens, elapsed as u64, schedule.duration as u64)?
+    };
+
+    Ok(milestone_amount.max(time_amount))
+}
+
+// Tokens the investor can withdraw now: the vested amount (or everything after a full unlock) minus what was already claimed
+fn claimable_amount(pool_config: &PoolConfig, lock_pool: &LockPool, user: &UserLockInfo, current_time: i64) -> Result<u64> {
+    released_amount(
+        pool_config,
+        lock_pool.start_time,
+        user,
+        lock_pool.current_milestone,
+        lock_pool.full_unlock_executed,
+        current_time,
+    )
+}
+
+// Tokens the open unlock job releases to `user`: as `claimable_amount`, with the pending milestone or full unlock applied
+fn pending_claimable_amount(pool_config: &PoolConfig, lock_pool: &LockPool, user: &UserLockInfo, current_time: i64) -> Result<u64> {
+    released_amount(
+        pool_config,
+        lock_pool.start_time,
+        user,
+        lock_pool.current_milestone.max(lock_pool.pending_milestone),
+        lock_pool.full_unlock_executed || lock_pool.pending_full_unlock,
+        current_time,
+    )
+}
+
+fn released_amount(
+    pool_config: &PoolConfig,
+    start_time: i64,
+    user: &UserLockInfo,
+    milestone: u8,
+    full_unlock: bool,
+    current_time: i64,
+) -> Result<u64> {
+    if full_unlock {
+        return Ok(user.locked_tokens);
+    }
+    Ok(vested_amount(pool_config, start_time, user, milestone, current_time)?
+        .saturating_sub(user.unlocked_tokens)
+        .min(user.locked_tokens))
+}
+
+// Pays out whatever `user` can claim now from the lock pool; returns the amount transferred
+fn settle_claim<'info>(
+    pool_config: &PoolConfig,
+    lock_pool: &mut Account<'info, LockPool>,
+    user: &mut Account<'info, UserLockInfo>,
+    lock_pool_token_account: &Account<'info, TokenAccount>,
+    user_token_account: &Account<'info, TokenAccount>,
+    pda: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<u64> {
+    require!(lock_pool.phase == PoolPhase::Sealed, CustomError::PoolNotSealed);
+    lock_pool.require_not_paused(PAUSE_CLAIMS)?;
+
+    // Amount unlocked by the current milestone, linear vesting (or full unlock) and not yet withdrawn
+    let current_time = Clock::get()?.unix_timestamp;
+    let claimable = claimable_amount(pool_config, lock_pool, user, current_time)?;
+    if claimable == 0 {
+        return Ok(0);
+    }
+
+    // Error: Ensure the lock pool holds the claimable amount
+    require!(
+        lock_pool_token_account.amount >= claimable,
+        CustomError::InsufficientPoolBalance
+    );
+
+    // Update user's token state before moving funds
+    user.release(claimable)?;
+    lock_pool.release(claimable)?;
+
+    // Transfer the claimable tokens from the lock pool to the investor
+    transfer_from_vault(
+        token_program,
+        lock_pool_token_account.to_account_info(),
+        user_token_account.to_account_info(),
+        pda,
+        &lock_pool.key(),
+        lock_pool.vault_authority_bump,
+        claimable,
+    )?;
+
+    emit!(TokensUnlocked {
+        lock_pool: lock_pool.key(),
+        user_wallet: user.user_wallet,
+        amount: claimable,
+        user_unlocked_tokens: user.unlocked_tokens,
+        user_locked_tokens: user.locked_tokens,
+    });
+
+    Ok(claimable)
+}
+
+// Checks `token_account` is the associated token account of `wallet` for the pool mint, creating it
+// (paid by `payer`) when it does not exist yet
+fn resolve_recipient_token_account<'info>(
+    wallet: &AccountInfo<'info>,
+    token_account: &'info AccountInfo<'info>,
+    mint: &Account<'info, Mint>,
+    payer: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    associated_token_program: &Program<'info, AssociatedToken>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    // ✅ Security Check: Recipients are paid to their associated token account only
+    require_keys_eq!(
+        token_account.key(),
+        get_associated_token_address(wallet.key, &mint.key()),
+        CustomError::InvalidRecipientTokenAccount
+    );
+
+    if token_account.data_is_empty() {
+        associated_token::create(CpiContext::new(
+            associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: payer.clone(),
+                associated_token: token_account.clone(),
+                authority: wallet.clone(),
+                mint: mint.to_account_info(),
+                system_program: system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
+        return Ok(());
+    }
+
+    // An existing account may have had its owner reassigned
+    let recipient = Account::<TokenAccount>::try_from(token_account)?;
+    require_keys_eq!(recipient.owner, wallet.key(), CustomError::InvalidRecipientTokenAccount);
+    require_keys_eq!(recipient.mint, mint.key(), CustomError::InvalidRecipientTokenAccount);
+    Ok(())
+}
+
+// Transfers out of a token account owned by the pool's vault authority PDA, signing with its seeds
+fn transfer_from_vault<'info>(
+    token_program: &Program<'info, Token>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    vault_authority: &AccountInfo<'info>,
+    lock_pool_key: &Pubkey,
+    vault_authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let bump = [vault_authority_bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[VAULT_AUTHORITY_SEED, lock_pool_key.as_ref(), &bump]];
+    let cpi_accounts = Transfer {
+        from,
+        to,
+        authority: vault_authority.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)
+}
+
+// Rejects tables that are not monotonic and rates outside their bounds
+fn validate_pool_config(params: &PoolConfigParams) -> Result<()> {
+    require!(params.milestone_thresholds[0] > 0, CustomError::InvalidPoolConfig);
+    for i in 1..MILESTONE_COUNT {
+        require!(
+            params.milestone_thresholds[i] > params.milestone_thresholds[i - 1],
+            CustomError::InvalidPoolConfig
+        );
+    }
+    for schedule in params.schedules.iter() {
+        for i in 1..MILESTONE_COUNT {
+            require!(
+                schedule.milestone_percentages[i] >= schedule.milestone_percentages[i - 1],
+                CustomError::InvalidPoolConfig
+            );
+        }
+        require!(
+            schedule.milestone_percentages[MILESTONE_COUNT - 1] <= 100,
+            CustomError::InvalidPoolConfig
+        );
+        require!(
+            schedule.duration >= 0 && schedule.cliff >= 0 && schedule.cliff <= schedule.duration,
+            CustomError::InvalidPoolConfig
+        );
+    }
+    require!(params.tax_bps <= MAX_TAX_BPS, CustomError::InvalidPoolConfig);
+    require!(params.burn_share_bps as u64 <= BPS_DENOMINATOR, CustomError::InvalidPoolConfig);
+    require!(params.auto_sell_bps as u64 <= BPS_DENOMINATOR, CustomError::InvalidPoolConfig);
+    require!(params.max_hold_amount > 0, CustomError::InvalidPoolConfig);
+    require!(params.full_unlock_delay > 0, CustomError::InvalidPoolConfig);
+    require!(
+        params.price_feed != Pubkey::default() || params.amm_pool != Pubkey::default(),
+        CustomError::InvalidPoolConfig
+    );
+    require!(
+        params.price_feed == Pubkey::default() || params.price_feed_id != [0; 32] || cfg!(feature = "localnet"),
+        CustomError::InvalidPoolConfig
+    );
+    require!(params.max_price_age > 0, CustomError::InvalidPoolConfig);
+    require!(params.max_confidence_bps as u64 <= BPS_DENOMINATOR, CustomError::InvalidPoolConfig);
+    require!(params.milestone_hold_secs >= 0, CustomError::InvalidPoolConfig);
+    Ok(())
+}
+
+// =============================================Oracle======================================================
+
+// Price read from a feed account, in quote units scaled by 10^expo
+pub struct PriceData {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+// Reads a Pyth PriceUpdateV2 account (or, in `localnet` builds, a program-owned ManualPriceFeed) and rejects unusable prices
+fn load_price(price_feed: &AccountInfo, pool_config: &PoolConfig, current_time: i64) -> Result<PriceData> {
+    let price = if price_feed.owner == &PYTH_RECEIVER_PROGRAM_ID {
+        parse_pyth_price(&price_feed.try_borrow_data()?, &pool_config.price_feed_id)?
+    } else if cfg!(feature = "localnet") && price_feed.owner == &crate::ID {
+        let data = price_feed.try_borrow_data()?;
+        let feed = ManualPriceFeed::try_deserialize(&mut &data[..])?;
+        PriceData {
+            price: feed.price,
+            conf: feed.conf,
+            expo: feed.expo,
+            publish_time: feed.publish_time,
+        }
+    } else {
+        return err!(CustomError::InvalidPriceFeed);
+    };
+
+    check_price(&price, pool_config, current_time)?;
+    Ok(price)
+}
+
+// Rejects a price that is not positive, older than `max_price_age` or with a confidence interval wider than `max_confidence_bps`
+fn check_price(price: &PriceData, pool_config: &PoolConfig, current_time: i64) -> Result<()> {
+    // ✅ Security Check: Price must be positive, fresh and tight enough to trust
+    require!(price.price > 0, CustomError::InvalidPriceFeed);
+    require!(
+        current_time.saturating_sub(price.publish_time) <= pool_config.max_price_age,
+        CustomError::StalePrice
+    );
+    require!(
+        price.conf as u128 * BPS_DENOMINATOR as u128
+            <= price.price as u128 * pool_config.max_confidence_bps as u128,
+        CustomError::PriceConfidenceTooWide
+    );
+
+    Ok(())
+}
+
+// Parses a fully verified Pyth pull-oracle PriceUpdateV2 account carrying `feed_id`
+fn parse_pyth_price(data: &[u8], feed_id: &[u8; 32]) -> Result<PriceData> {
+    require!(data.len() >= PYTH_PRICE_UPDATE_MIN_LEN, CustomError::InvalidPriceFeed);
+    require!(data[..8] == PYTH_PRICE_UPDATE_DISCRIMINATOR, CustomError::InvalidPriceFeed);
+
+    // ✅ Security Check: Only updates checked against the full Wormhole guardian set
+    require!(data[40] == PYTH_VERIFICATION_FULL, CustomError::InvalidPriceFeed);
+
+    // Price message follows write_authority (32) and the one-byte `Full` verification level
+    let message = &data[41..];
+    let read_i32 = |offset: usize| i32::from_le_bytes(message[offset..offset + 4].try_into().unwrap());
+    let read_u64 = |offset: usize| u64::from_le_bytes(message[offset..offset + 8].try_into().unwrap());
+    let read_i64 = |offset: usize| i64::from_le_bytes(message[offset..offset + 8].try_into().unwrap());
+
+    require!(message[..32] == feed_id[..], CustomError::InvalidPriceFeed); // Feed configured for the pool
+
+    Ok(PriceData {
+        price: read_i64(32),        // price_message.price
+        conf: read_u64(40),         // price_message.conf
+        expo: read_i32(48),         // price_message.exponent
+        publish_time: read_i64(52), // price_message.publish_time
+    })
+}
+
+// Market cap in whole quote units: circulating supply (base units) * price / 10^decimals
+fn market_cap_from_price(circulating_supply: u64, decimals: u8, price: &PriceData) -> Result<u64> {
+    let value = circulating_supply as u128 * price.price as u128;
+    let exponent = price.expo - decimals as i32;
+    let scale = 10u128
+        .checked_pow(exponent.unsigned_abs())
+        .ok_or(CustomError::InvalidPriceFeed)?;
+    let market_cap = if exponent >= 0 {
+        value.checked_mul(scale).ok_or(CustomError::InvalidPriceFeed)?
+    } else {
+        value / scale
+    };
+    Ok(u64::try_from(market_cap).unwrap_or(u64::MAX))
+}
+
+// =============================================AMM=========================================================
+
+// Spot price of the pool token in raw quote units per raw token unit
+pub enum SpotPrice {
+    Reserves { token_reserve: u64, quote_reserve: u64 }, // Constant-product pool: quote / token
+    SqrtPriceX64 { sqrt_price: u128, token_is_a: bool }, // Concentrated liquidity: (sqrt_price / 2^64)^2 = b per a
+}
+
+// Reads spot price from a Raydium CPMM PoolState or an Orca Whirlpool and its vaults
+fn load_amm_spot_price(
+    amm_pool: &AccountInfo,
+    mint: &Pubkey,
+    quote_mint: &Pubkey,
+    token_vault: &Account<TokenAccount>,
+    quote_vault: &Account<TokenAccount>,
+) -> Result<SpotPrice> {
+    let data = amm_pool.try_borrow_data()?;
+    if amm_pool.owner == &RAYDIUM_PROGRAM_ID_MAIN_1 || amm_pool.owner == &RAYDIUM_PROGRAM_ID_DEV_1 {
+        let fees = parse_raydium_cpmm_pool(&data, &token_vault.key(), &quote_vault.key(), mint, quote_mint)?;
+        // Protocol and fund fees sit in the vaults but are not tradable liquidity
+        Ok(SpotPrice::Reserves {
+            token_reserve: token_vault.amount.saturating_sub(fees.token_fees),
+            quote_reserve: quote_vault.amount.saturating_sub(fees.quote_fees),
+        })
+    } else if amm_pool.owner == &ORCA_PROGRAM_ID_MAIN_1 {
+        parse_orca_whirlpool(&data, &token_vault.key(), &quote_vault.key(), mint, quote_mint)
+    } else {
+        err!(CustomError::InvalidAmmPool)
+    }
+}
+
+// Fees accrued in the vaults of a Raydium CPMM pool, oriented to (token, quote)
+pub struct CpmmFees {
+    pub token_fees: u64,
+    pub quote_fees: u64,
+}
+
+// Parses a Raydium CPMM PoolState and checks the passed vaults and mints belong to it
+fn parse_raydium_cpmm_pool(
+    data: &[u8],
+    token_vault: &Pubkey,
+    quote_vault: &Pubkey,
+    mint: &Pubkey,
+    quote_mint: &Pubkey,
+) -> Result<CpmmFees> {
+    require!(data.len() >= RAYDIUM_CPMM_POOL_MIN_LEN, CustomError::InvalidAmmPool);
+    require!(data[..8] == RAYDIUM_CPMM_POOL_DISCRIMINATOR, CustomError::InvalidAmmPool);
+
+    let read_key = |offset: usize| Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap());
+    let read_u64 = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+    let (vault_0, vault_1) = (read_key(72), read_key(104)); // token_0_vault, token_1_vault
+    let (mint_0, mint_1) = (read_key(168), read_key(200)); // token_0_mint, token_1_mint
+    // protocol_fees_token_{0,1} + fund_fees_token_{0,1}
+    let fees_0 = read_u64(341).saturating_add(read_u64(357));
+    let fees_1 = read_u64(349).saturating_add(read_u64(365));
+
+    if (mint_0, mint_1) == (*mint, *quote_mint) && (vault_0, vault_1) == (*token_vault, *quote_vault) {
+        Ok(CpmmFees { token_fees: fees_0, quote_fees: fees_1 })
+    } else if (mint_1, mint_0) == (*mint, *quote_mint) && (vault_1, vault_0) == (*token_vault, *quote_vault) {
+        Ok(CpmmFees { token_fees: fees_1, quote_fees: fees_0 })
+    } else {
+        err!(CustomError::InvalidAmmPool)
+    }
+}
+
+// Parses an Orca Whirlpool and checks the passed vaults and mints belong to it
+fn parse_orca_whirlpool(
+    data: &[u8],
+    token_vault: &Pubkey,
+    quote_vault: &Pubkey,
+    mint: &Pubkey,
+    quote_mint: &Pubkey,
+) -> Result<SpotPrice> {
+    require!(data.len() >= ORCA_WHIRLPOOL_MIN_LEN, CustomError::InvalidAmmPool);
+    require!(data[..8] == ORCA_WHIRLPOOL_DISCRIMINATOR, CustomError::InvalidAmmPool);
+
+    let read_key = |offset: usize| Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap());
+    let sqrt_price = u128::from_le_bytes(data[65..81].try_into().unwrap()); // Q64.64
+
+    let (mint_a, vault_a) = (read_key(101), read_key(133)); // token_mint_a, token_vault_a
+    let (mint_b, vault_b) = (read_key(181), read_key(213)); // token_mint_b, token_vault_b
+
+    let token_is_a = if (mint_a, mint_b) == (*mint, *quote_mint) && (vault_a, vault_b) == (*token_vault, *quote_vault) {
+        true
+    } else if (mint_b, mint_a) == (*mint, *quote_mint) && (vault_b, vault_a) == (*token_vault, *quote_vault) {
+        false
+    } else {
+        return err!(CustomError::InvalidAmmPool);
+    };
+    require!(sqrt_price > 0, CustomError::InvalidAmmPool);
+
+    Ok(SpotPrice::SqrtPriceX64 { sqrt_price, token_is_a })
+}
+
+// Market cap in whole quote units: circulating supply (raw) * spot price / 10^quote_decimals
+fn market_cap_from_spot_price(circulating_supply: u64, quote_decimals: u8, spot_price: &SpotPrice) -> Result<u64> {
+    let supply = circulating_supply as u128;
+    let value = match *spot_price {
+        SpotPrice::Reserves { token_reserve, quote_reserve } => {
+            require!(token_reserve > 0 && quote_reserve > 0, CustomError::InvalidAmmPool);
+            supply * quote_reserve as u128 / token_reserve as u128
+        }
+        // price(b per a) = sqrt_price^2 / 2^128
+        SpotPrice::SqrtPriceX64 { sqrt_price, token_is_a: true } => {
+            mul_shr_64(supply, sqrt_price)
+                .and_then(|v| mul_shr_64(v, sqrt_price))
+                .ok_or(CustomError::MarketCapOverflow)?
+        }
+        // price(a per b) = 2^128 / sqrt_price^2
+        SpotPrice::SqrtPriceX64 { sqrt_price, token_is_a: false } => {
+            ((supply << 64) / sqrt_price)
+                .checked_mul(1u128 << 64)
+                .map(|v| v / sqrt_price)
+                .ok_or(CustomError::MarketCapOverflow)?
+        }
+    };
+    let scale = 10u128
+        .checked_pow(quote_decimals as u32)
+        .ok_or(CustomError::MarketCapOverflow)?;
+    Ok(u64::try_from(value / scale).unwrap_or(u64::MAX))
+}
+
+// (a * b) >> 64 without overflowing the 256-bit intermediate product
+fn mul_shr_64(a: u128, b: u128) -> Option<u128> {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+    (a_hi * b_hi)
+        .checked_mul(1u128 << 64)?
+        .checked_add(a_hi * b_lo)?
+        .checked_add(a_lo * b_hi)?
+        .checked_add((a_lo * b_lo) >> 64)
+}
+
+// =============================================UserLockInfo================================================
+
+// A wallet of the batch with all its `users` entries summed
+struct BatchInvestor {
+    wallet: Pubkey,
+    category: InvestorCategory,
+    token_amount: u64,
+    entries: u32, // Number of `users` entries merged into this one
+}
+
+// Merges repeated wallets of a batch, keeping the order of first appearance
+fn merge_batch_investors(users: &[InvestorInfo]) -> Result<Vec<BatchInvestor>> {
+    let mut investors: Vec<BatchInvestor> = Vec::with_capacity(users.len());
+    for user in users {
+        // ✅ Security Check: Ensure token amount is valid
+        require!(user.token_amount > 0, CustomError::InvalidTokenAmount);
+
+        match investors.iter_mut().find(|i| i.wallet == user.wallet_address) {
+            Some(investor) => {
+                // A wallet has a single vesting schedule
+                require!(investor.category == user.category, CustomError::InvestorCategoryMismatch);
+                investor.token_amount = investor
+                    .token_amount
+                    .checked_add(user.token_amount)
+                    .ok_or(CustomError::MathOverflow)?;
+                investor.entries += 1;
+            }
+            None => investors.push(BatchInvestor {
+                wallet: user.wallet_address,
+                category: user.category,
+                token_amount: user.token_amount,
+                entries: 1,
+            }),
+        }
+    }
+    Ok(investors)
+}
+
+// Funds the vault for the whole batch and creates (or tops up) each investor's UserLockInfo PDA
+fn append_investor_batch<'info>(
+    accounts: &mut InitializeLockAccounts<'info>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    users: &[InvestorInfo],
+) -> Result<()> {
+    let lock_pool = &mut accounts.lock_pool_account;
+    let lock_pool_key = lock_pool.key();
+
+    let investors = merge_batch_investors(users)?;
+    // One UserLockInfo PDA per distinct wallet, in order of first appearance in `users`
+    require!(
+        remaining_accounts.len() == investors.len(),
+        CustomError::UserLockAccountsMismatch
+    );
+
+    // Step 1: Transfer the batch total to the shared lock pool token account in a single CPI
+    let batch_total = investors
+        .iter()
+        .try_fold(0u64, |total, investor| total.checked_add(investor.token_amount))
+        .ok_or(CustomError::MathOverflow)?;
+    let cpi_accounts = Transfer {
+        from: accounts.source_wallet.to_account_info(), // Admin's source wallet
+        to: accounts.lock_pool_token_account.to_account_info(), // Centralized lock pool
+        authority: accounts.admin_wallet.to_account_info(), // Admin wallet signature
+    };
+    let cpi_ctx = CpiContext::new(accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, batch_total)?;
+
+    for (investor, user_lock_info) in investors.iter().zip(remaining_accounts.iter()) {
+        // Step 2: Create the investor's UserLockInfo PDA, or merge into the one of an earlier batch
+        let existing_record = !user_lock_info.data_is_empty();
+        let (user_total_tokens, user_locked_tokens) = if existing_record {
+            let mut user_lock = load_user_lock_account(&lock_pool_key, user_lock_info)?;
+            // ✅ Security Check: Ensure the record is the investor's PDA
+            require_keys_eq!(user_lock.user_wallet, investor.wallet, CustomError::InvalidUserLockAccount);
+            require!(user_lock.category == investor.category, CustomError::InvestorCategoryMismatch);
+
+            user_lock.add_locked(investor.token_amount)?;
+            user_lock.exit(&crate::ID)?;
+            (user_lock.total_tokens, user_lock.locked_tokens)
+        } else {
+            let index = lock_pool.register_holder()?;
+            create_user_lock_account(
+                lock_pool_key,
+                NewUserLock {
+                    index,
+                    wallet: investor.wallet,
+                    category: investor.category,
+                    token_amount: investor.token_amount,
+                },
+                user_lock_info,
+                &accounts.admin_wallet.to_account_info(),
+                &accounts.system_program.to_account_info(),
+            )?;
+            (investor.token_amount, investor.token_amount)
+        };
+
+        // Update pool aggregates
+        lock_pool.add_locked(investor.token_amount)?;
+
+        emit!(InvestorLocked {
+            lock_pool: lock_pool_key,
+            user_wallet: investor.wallet,
+            amount: investor.token_amount,
+            user_total_tokens,
+            user_locked_tokens,
+            total_locked: lock_pool.total_locked,
+            holder_count: lock_pool.holder_count,
+        });
+
+        // Report repeated wallets so the presale can be reconciled
+        if existing_record || investor.entries > 1 {
+            emit!(InvestorMerged {
+                lock_pool: lock_pool_key,
+                user_wallet: investor.wallet,
+                batch_entries: investor.entries,
+                existing_record,
+                amount: investor.token_amount,
+                user_total_tokens,
+            });
+        }
+    }
+
+    // ✅ Security Check: The vault must back every token locked so far; tokens sent to it directly are
+    // surplus for `reclaim_dust`, not a reason to block the upload
+    accounts.lock_pool_token_account.reload()?;
+    require!(
+        accounts.lock_pool_token_account.amount >= lock_pool.total_locked,
+        CustomError::VaultBalanceMismatch
+    );
+
+    Ok(())
+}
+
+// Fields of a UserLockInfo record created from an investor list
+struct NewUserLock {
+    index: u64,
+    wallet: Pubkey,
+    category: InvestorCategory,
+    token_amount: u64, // Locked in full; nothing is unlocked yet
+}
+
+// Creates and initializes the UserLockInfo PDA of `new_user.wallet` for the given pool
+fn create_user_lock_account<'info>(
+    lock_pool_key: Pubkey,
+    new_user: NewUserLock,
+    user_lock_info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let NewUserLock { index, wallet, category, token_amount } = new_user;
+    let (expected_key, bump) = Pubkey::find_program_address(
+        &[USER_LOCK_SEED, lock_pool_key.as_ref(), wallet.as_ref()],
+        &crate::ID,
+    );
+    // ✅ Security Check: Ensure the passed account is the investor's record PDA
+    require_keys_eq!(user_lock_info.key(), expected_key, CustomError::InvalidUserLockAccount);
+    require!(user_lock_info.data_is_empty(), CustomError::AlreadyInitialized);
+
+    let space = 8 + UserLockInfo::INIT_SPACE;
+    let lamports = Rent::get()?.minimum_balance(space);
+    let signer_seeds: &[&[&[u8]]] = &[&[USER_LOCK_SEED, lock_pool_key.as_ref(), wallet.as_ref(), &[bump]]];
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::CreateAccount {
+                from: payer.clone(),
+                to: user_lock_info.clone(),
+            },
+            signer_seeds,
+        ),
+        lamports,
+        space as u64,
+        &crate::ID,
+    )?;
+
+    let user_lock = UserLockInfo {
+        lock_pool: lock_pool_key,
+        index,
+        user_wallet: wallet,
+        category,
+        total_tokens: token_amount,
+        unlocked_tokens: 0, // Start with 0 unlocked tokens
+        locked_tokens: token_amount,
+        allocation_registered: false,
+        bump,
+    };
+    let mut data = user_lock_info.try_borrow_mut_data()?;
+    user_lock.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
+
+// Decodes an unversioned LockPoolState; the whole payload after the discriminator must be consumed
+fn read_legacy_pool(data: &[u8], legacy_layout: LegacyLayout) -> Result<LegacyLockPoolWithMilestones> {
+    // ✅ Security Check: A pool already converted to a LockPool is never migrated twice
+    require!(
+        data.get(..8) != Some(&<LockPool as anchor_lang::Discriminator>::DISCRIMINATOR[..]),
+        CustomError::AlreadyInitialized
+    );
+    require!(data.get(..8) == Some(&LEGACY_LOCK_POOL_DISCRIMINATOR[..]), CustomError::InvalidLegacyLayout);
+
+    let mut payload: &[u8] = &data[8..];
+    let legacy = match legacy_layout {
+        LegacyLayout::Original => LegacyLockPoolOriginal::deserialize(&mut payload)
+            .map(LegacyLockPoolWithMilestones::from),
+        LegacyLayout::WithMilestones => LegacyLockPoolWithMilestones::deserialize(&mut payload),
+    }
+    .map_err(|_| error!(CustomError::InvalidLegacyLayout))?;
+    // Anything but zero padding after the decoded fields means the layout was guessed wrong
+    require!(payload.iter().all(|byte| *byte == 0), CustomError::InvalidLegacyLayout);
+
+    Ok(legacy)
+}
+
+// Sums repeated legacy entries per wallet, keeping first-appearance order
+fn merge_legacy_users(users: &[LegacyUserLockInfo]) -> Result<Vec<LegacyUserLockInfo>> {
+    let mut holders: Vec<LegacyUserLockInfo> = Vec::with_capacity(users.len());
+    for user in users {
+        // ✅ Security Check: Every legacy entry must account for all of its tokens
+        require!(
+            user.unlocked_tokens.checked_add(user.locked_tokens) == Some(user.total_tokens),
+            CustomError::LockedSumMismatch
+        );
+        match holders.iter_mut().find(|holder| holder.user_wallet == user.user_wallet) {
+            Some(holder) => {
+                holder.total_tokens = holder.total_tokens.checked_add(user.total_tokens).ok_or(CustomError::MathOverflow)?;
+                holder.unlocked_tokens = holder.unlocked_tokens.checked_add(user.unlocked_tokens).ok_or(CustomError::MathOverflow)?;
+                holder.locked_tokens = holder.locked_tokens.checked_add(user.locked_tokens).ok_or(CustomError::MathOverflow)?;
+            }
+            None => holders.push(user.clone()),
+        }
+    }
+    Ok(holders)
+}
+
+// Settings of a migrated pool that the legacy layouts never stored
+struct MigratedPoolSettings {
+    mint: Pubkey,
+    admin_wallet: Pubkey, // Also receives every role
+    lock_pool_token_account: Pubkey,
+    token_pool_account: Pubkey,
+    vault_authority_bump: u8,
+    timelock_delay: i64,
+    max_holders: u64,
+}
+
+// Builds the LockPool of a legacy pool once `migration` has recreated all of its holders. `total_locked`
+// is the holders' locked sum: the legacy total was never updated by unlocks or purchases, so it is only reported.
+fn convert_legacy_pool(
+    legacy: &LegacyLockPoolWithMilestones,
+    migration: &PoolMigration,
+    settings: MigratedPoolSettings,
+) -> Result<LockPool> {
+    let holders = merge_legacy_users(&legacy.users)?;
+    // ✅ Security Check: Every legacy holder has its record before the pool is rewritten
+    require!(migration.holder_cursor == holders.len() as u64, CustomError::MigrationIncomplete);
+    require!(migration.holder_cursor <= settings.max_holders, CustomError::PoolFull);
+
+    Ok(LockPool {
+        version: LOCK_POOL_VERSION,
+        phase: PoolPhase::Loading, // Re-sealed by `seal_pool` once the vault is funded
+        total_locked: migration.total_locked,
+        holder_count: migration.holder_cursor,
+        start_time: legacy.start_time, // Kept by `seal_pool`
+        current_milestone: legacy.current_milestone,
+        full_unlock_executed: legacy.full_unlock_executed,
+        is_max_hold_limit_active: legacy.is_max_hold_limit_active,
+        unlock_in_progress: false,
+        pending_milestone: 0,
+        pending_market_cap: 0,
+        pending_full_unlock: false,
+        unlock_cursor: 0,
+        milestone_observed_at: [0; MILESTONE_COUNT],
+        allocation_root: [0; 32],
+        unregistered_allocation: 0,
+        vault_authority_bump: settings.vault_authority_bump,
+        mint: settings.mint,
+        admin_wallet: settings.admin_wallet,
+        lock_pool_token_account: settings.lock_pool_token_account,
+        token_pool_account: settings.token_pool_account,
+        bump: 0, // Legacy pools live at a keypair address, not `[LOCK_POOL_SEED, mint]`
+        dust_reclaimed: 0,
+        pending_admin_wallet: Pubkey::default(),
+        milestone_keeper: settings.admin_wallet,
+        treasury: settings.admin_wallet,
+        pauser: settings.admin_wallet,
+        multisig: Pubkey::default(),
+        timelock_delay: settings.timelock_delay,
+        queued_change_count: 0,
+        paused_flags: 0,
+        max_holders: settings.max_holders,
+    })
+}
+
+// Loads a UserLockInfo record passed through remaining accounts and checks it belongs to the pool
+fn load_user_lock_account<'info>(
+    lock_pool_key: &Pubkey,
+    user_lock_info: &'info AccountInfo<'info>,
+) -> Result<Account<'info, UserLockInfo>> {
+    let user_lock: Account<'info, UserLockInfo> = Account::try_from(user_lock_info)?;
+    require_keys_eq!(user_lock.lock_pool, *lock_pool_key, CustomError::InvalidUserLockAccount);
+    Ok(user_lock)
+}
+
+// Opens a batched unlock job; a pool without holders completes it immediately
+fn start_unlock_job(lock_pool_key: Pubkey, lock_pool: &mut LockPool, pool_config: &PoolConfig, current_time: i64) {
+    lock_pool.unlock_in_progress = true;
+    lock_pool.unlock_cursor = 0;
+    if lock_pool.holder_count == 0 {
+        complete_unlock_job(lock_pool_key, lock_pool, pool_config, current_time);
+    }
+}
+
+// Commits the pending milestone (or full unlock) and closes the job
+fn complete_unlock_job(lock_pool_key: Pubkey, lock_pool: &mut LockPool, pool_config: &PoolConfig, current_time: i64) {
+    if lock_pool.pending_full_unlock {
+        lock_pool.full_unlock_executed = true;
+        emit!(FullUnlockExecuted {
+            lock_pool: lock_pool_key,
+            holder_count: lock_pool.holder_count,
+            total_locked: lock_pool.total_locked,
+            timestamp: current_time,
+        });
+    } else {
+        lock_pool.current_milestone = lock_pool.pending_milestone;
+        emit!(MilestoneAdvanced {
+            lock_pool: lock_pool_key,
+            milestone: lock_pool.current_milestone,
+            percentage: milestone_percentage_from_milestone(
+                pool_config.schedule(InvestorCategory::Presale),
+                lock_pool.current_milestone,
+            ),
+            market_cap: lock_pool.pending_market_cap,
+            timestamp: current_time,
+        });
+    }
+    close_unlock_job(lock_pool);
+}
+
+// Closes the job and drops its pending state (committed by `complete_unlock_job`, rolled back by an abort)
+fn close_unlock_job(lock_pool: &mut LockPool) {
+    lock_pool.unlock_in_progress = false;
+    lock_pool.pending_milestone = 0;
+    lock_pool.pending_market_cap = 0;
+    lock_pool.pending_full_unlock = false;
+    lock_pool.unlock_cursor = 0;
+}
+
+// =============================================Multisig====================================================
+
+// Position of `signer` in the multisig signer set
+fn multisig_signer_index(multisig: &Multisig, signer: &Pubkey) -> Result<usize> {
+    multisig
+        .signers
+        .iter()
+        .position(|key| key == signer)
+        .ok_or_else(|| error!(CustomError::NotMultisigSigner))
+}
+
+// Marks the proposal authorizing `action` as executed; a pool without a multisig needs none
+fn consume_proposal(
+    lock_pool: &LockPool,
+    proposal: Option<&mut Account<Proposal>>,
+    action: ProposalAction,
+    current_time: i64,
+) -> Result<()> {
+    if lock_pool.multisig == Pubkey::default() {
+        return Ok(());
+    }
+    let proposal = proposal.ok_or(CustomError::ProposalRequired)?;
+
+    // ✅ Security Check: An active, unexpired proposal of this pool's multisig, approved for exactly this call
+    require_keys_eq!(proposal.multisig, lock_pool.multisig, CustomError::InvalidMultisig);
+    require!(proposal.status == ProposalStatus::Active, CustomError::ProposalNotActive);
+    require!(current_time < proposal.expires_at, CustomError::ProposalExpired);
+    require!(proposal.action == action, CustomError::ProposalActionMismatch);
+    require!(
+        proposal.approvals.count_ones() >= proposal.threshold as u32,
+        CustomError::ProposalNotApproved
+    );
+
+    proposal.status = ProposalStatus::Executed;
+    emit!(ProposalExecuted {
+        multisig: proposal.multisig,
+        proposal: proposal.key(),
+        action,
+    });
+
+    Ok(())
+}
+
+// =============================================Allocation Merkle tree======================================
+
+// Leaf of the allocation tree; shared with the off-chain builder in `tools/allocation-merkle`
+pub fn allocation_leaf(wallet: &Pubkey, amount: u64, category: InvestorCategory) -> [u8; 32] {
+    keccak::hashv(&[
+        &[MERKLE_LEAF_PREFIX],
+        wallet.as_ref(),
+        &amount.to_le_bytes(),
+        &[category as u8],
+    ])
+    .to_bytes()
+}
+
+// Parent of two nodes; children are sorted so proofs do not need left/right flags
+pub fn hash_allocation_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    keccak::hashv(&[&[MERKLE_NODE_PREFIX], first, second]).to_bytes()
+}
+
+pub fn verify_allocation_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_allocation_pair(&node, sibling));
+    computed == *root
+}
+
+// =============================================TransferHook================================================
+
+
+pub fn process_transfer_hook(
+    ctx: Context<TransferHookContext>,
+    transfer_amount: u64, // Amount of the transfer being hooked
+) -> Result<()> {
+    // Extract the owners of the source and destination token accounts
+    let source_program_id = &ctx.accounts.source_wallet.owner; // Authority of the source token account
+    let destination_program_id = &ctx.accounts.destination_wallet.owner; // Authority of the destination token account
+
+    // Check if it's a DEX transaction
+    let is_dex = is_dex_transaction(source_program_id, destination_program_id);
+
+    if is_dex {
+        ctx.accounts.lock_pool_account.require_not_paused(PAUSE_TAX)?;
+
+        // Tax Logic
+        let pool_config = &ctx.accounts.pool_config;
+        let tax = mul_div_floor(transfer_amount, pool_config.tax_bps as u64, BPS_DENOMINATOR)?; // Configured total tax
+        let burn_amount = mul_div_floor(tax, pool_config.burn_share_bps as u64, BPS_DENOMINATOR)?; // Burn share
+        let marketing_amount = tax.checked_sub(burn_amount).ok_or(CustomError::MathOverflow)?; // Remainder for Marketing Wallet
+        let net_transfer = transfer_amount.checked_sub(tax).ok_or(CustomError::MathOverflow)?;
+        let lock_pool = &mut ctx.accounts.lock_pool_account;
+        let lock_pool_key = lock_pool.key();
+
+        if lock_pool.is_max_hold_limit_active {
+            let user_hold_amount = ctx
+                .accounts
+                .user_lock_account
+                .total_tokens
+                .checked_add(net_transfer)
+                .ok_or(CustomError::MathOverflow)?;
+        
+            require!(
+                user_hold_amount <= pool_config.max_hold_amount,
+                CustomError::MaxHoldExceeded
+            );
+        }
+    
+        // Burn tokens
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            ctx.accounts.source_wallet.to_account_info(),
+            ctx.accounts.burn_wallet.to_account_info(),
+            &ctx.accounts.authority,
+            &lock_pool_key,
+            lock_pool.vault_authority_bump, // PDA signer
+            burn_amount,
+        )?;
+
+        // Send tokens to marketing wallet
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            ctx.accounts.source_wallet.to_account_info(),
+            ctx.accounts.marketing_wallet.to_account_info(),
+            &ctx.accounts.authority,
+            &lock_pool_key,
+            lock_pool.vault_authority_bump, // PDA signer
+            marketing_amount,
+        )?;
+
+        emit!(TaxCollected {
+            lock_pool: lock_pool_key,
+            user_wallet: ctx.accounts.user_wallet.key(),
+            transfer_amount,
+            tax,
+            burn_amount,
+            marketing_amount,
+            net_transfer,
+        });
+
+        // Update the final amount to be transferred
+        // ctx.accounts.transfer_instruction.amount = net_transfer;
+        
+        // Step: Transfer tokens to the shared lock pool token account
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            ctx.accounts.source_wallet.to_account_info(),
+            ctx.accounts.lock_pool_token_account.to_account_info(),
+            &ctx.accounts.authority,
+            &lock_pool_key,
+            lock_pool.vault_authority_bump, // PDA signer
+            net_transfer,
+        )?;
+        
+
+        // Register the buyer's UserLockInfo PDA on first transfer
+        let user = &mut ctx.accounts.user_lock_account;
+        if user.user_wallet == Pubkey::default() {
+            user.lock_pool = lock_pool_key;
+            user.index = lock_pool.register_holder()?;
+            user.user_wallet = ctx.accounts.user_wallet.key(); // Buyer’s wallet
+            user.category = InvestorCategory::Presale; // Market buyers follow the presale curve
+            user.bump = ctx.bumps.user_lock_account;
+        }
+
+        // Update the buyer's locked tokens
+        user.add_locked(net_transfer)?;
+        lock_pool.add_locked(net_transfer)?;
+
+        emit!(InvestorLocked {
+            lock_pool: lock_pool_key,
+            user_wallet: user.user_wallet,
+            amount: net_transfer,
+            user_total_tokens: user.total_tokens,
+            user_locked_tokens: user.locked_tokens,
+            total_locked: lock_pool.total_locked,
+            holder_count: lock_pool.holder_count,
+        });
+
+    }
+
+    // Allow the transfer to proceed
+    Ok(())
+}
+pub const POOL_CONFIG_SEED: &[u8] = b"pool_config"; // Seed prefix of the per-pool PoolConfig PDA
+pub const MILESTONE_COUNT: usize = 8; // Number of market-cap milestones in the unlock table
+pub const CATEGORY_COUNT: usize = 4; // Number of InvestorCategory variants
+pub const MERKLE_LEAF_PREFIX: u8 = 0; // Domain separator of allocation leaves
+pub const MERKLE_NODE_PREFIX: u8 = 1; // Domain separator of allocation tree nodes
+pub const BPS_DENOMINATOR: u64 = 10_000; // Basis points in 100%
+pub const MAX_TAX_BPS: u16 = 1_000; // Upper bound on the DEX transfer tax (10%)
+pub const LOCK_POOL_SEED: &[u8] = b"lock_pool"; // Seed prefix of the per-mint LockPool PDA
+pub const VAULT_SEED: &[u8] = b"vault"; // Seed prefix of the per-pool lock vault token account
+pub const SALE_POOL_SEED: &[u8] = b"sale_pool"; // Seed prefix of the per-pool sale pool token account
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault_authority"; // Seed prefix of the per-pool vault authority PDA
+pub const MULTISIG_SEED: &[u8] = b"multisig"; // Seed prefix of the per-pool Multisig PDA
+pub const PROPOSAL_SEED: &[u8] = b"proposal"; // Seed prefix of Proposal PDAs (multisig, index)
+pub const MAX_MULTISIG_SIGNERS: usize = 16; // Fits the `Proposal.approvals` bitmap
+pub const QUEUED_CHANGE_SEED: &[u8] = b"queued_change"; // Seed prefix of QueuedChange PDAs (lock_pool, index)
+pub const POOL_MIGRATION_SEED: &[u8] = b"pool_migration"; // Seed prefix of the per-pool PoolMigration PDA
+pub const POOL_AUDIT_SEED: &[u8] = b"pool_audit"; // Seed prefix of PoolAudit PDAs (lock_pool, auditor)
+pub const MIN_TIMELOCK_DELAY: i64 = 24 * 60 * 60; // Holders get at least a day to react to a queued change
+pub const LOCK_POOL_VERSION: u8 = 2; // LockPool layout through `max_holders` (bump when appending fields)
+pub const LEGACY_LOCK_POOL_DISCRIMINATOR: [u8; 8] = [35, 87, 116, 241, 246, 77, 247, 12]; // "account:LockPoolState", shared by every LegacyLayout
+pub const PAUSE_PURCHASES: u8 = 1 << 0; // Halts `purchase_tokens`
+pub const PAUSE_UNLOCKS: u8 = 1 << 1; // Halts milestone checks, full unlock and unlock batches
+pub const PAUSE_CLAIMS: u8 = 1 << 2; // Halts `claim` and `claim_with_proof`
+pub const PAUSE_TAX: u8 = 1 << 3; // Halts the transfer-hook tax path
+pub const PAUSE_AUTO_SELL: u8 = 1 << 4; // Halts `finalize_unlock`
+pub const PAUSE_ALL: u8 = PAUSE_PURCHASES | PAUSE_UNLOCKS | PAUSE_CLAIMS | PAUSE_TAX | PAUSE_AUTO_SELL;
+pub const USER_LOCK_SEED: &[u8] = b"user_lock"; // Seed prefix of the per-investor UserLockInfo PDA
+pub const MANUAL_PRICE_FEED_SEED: &[u8] = b"manual_price_feed"; // Seed prefix of the ManualPriceFeed PDA
+// Pyth oracle (pull-oracle receiver; the legacy v2 push price accounts are no longer updated)
+const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ"); // MainNET and DevNET
+const PYTH_PRICE_UPDATE_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205]; // "account:PriceUpdateV2"
+const PYTH_VERIFICATION_FULL: u8 = 1; // VerificationLevel::Full
+const PYTH_PRICE_UPDATE_MIN_LEN: usize = 133; // Through posted_slot of a `Full` update
+// AMM account layouts
+const RAYDIUM_CPMM_POOL_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70]; // "account:PoolState"
+const RAYDIUM_CPMM_POOL_MIN_LEN: usize = 373; // Through fund_fees_token_1
+const ORCA_WHIRLPOOL_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9]; // "account:Whirlpool"
+const ORCA_WHIRLPOOL_MIN_LEN: usize = 245; // Through token_vault_b
+// Known DEX program IDs
+const SERUM_DEX_PROGRAM_ID_DEV_1: Pubkey = pubkey!("DESVgJVGajEgKGXhb6XmqDHGz3VjdgP7rEVESBgxmroY"); // DevNET
+const SERUM_DEX_PROGRAM_ID_MAIN_1: Pubkey = pubkey!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin"); // MainNET
+const RAYDIUM_PROGRAM_ID_DEV_1: Pubkey = pubkey!("CPMDWBwJDtYax9qW7AyRuVC19Cc4L4Vcy4n2BHAbHkCW");    // DevNET
+const RAYDIUM_PROGRAM_ID_DEV_2: Pubkey = pubkey!("HWy1jotHpo6UqeQxx49dpYYdQB8wj9Qk9MdxwjLvDHB8");    // DevNET
+const RAYDIUM_PROGRAM_ID_DEV_3: Pubkey = pubkey!("DDg4VmQaJV9ogWce7LpcjBA9bv22wRp5uaTPa5pGjijF");    // DevNET
+const RAYDIUM_PROGRAM_ID_DEV_4: Pubkey = pubkey!("devi51mZmdwUJGU9hjN27vEz64Gps7uUefqxg27EAtH");    // DevNET
+const RAYDIUM_PROGRAM_ID_DEV_5: Pubkey = pubkey!("85BFyr98MbCUU9MVTEgzx1nbhWACbJqLzho6zd6DZcWL");    // DevNET
+const RAYDIUM_PROGRAM_ID_DEV_6: Pubkey = pubkey!("EcLzTrNg9V7qhcdyXDe2qjtPkiGzDM2UbdRaeaadU5r2");    // DevNET
+const RAYDIUM_PROGRAM_ID_DEV_7: Pubkey = pubkey!("BVChZ3XFEwTMUk1o9i3HAf91H6mFxSwa5X2wFAWhYPhU");    // DevNET
+const RAYDIUM_PROGRAM_ID_MAIN_1: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");    // MainNET
+const RAYDIUM_PROGRAM_ID_MAIN_2: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");    // MainNET
+const RAYDIUM_PROGRAM_ID_MAIN_3: Pubkey = pubkey!("5quBtoiQqxF9Jv6KYKctB59NT3gtJD2Y65kdnB1Uev3h");    // MainNET
+const RAYDIUM_PROGRAM_ID_MAIN_4: Pubkey = pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");    // MainNET
+const RAYDIUM_PROGRAM_ID_MAIN_5: Pubkey = pubkey!("routeUGWgWzqBWFcrCfv8tritsqukccJPu3q5GPP3xS");    // MainNET
+const RAYDIUM_PROGRAM_ID_MAIN_6: Pubkey = pubkey!("EhhTKczWMGQt46ynNeRX1WfeagwwJd7ufHvCDjRxjo5Q");    // MainNET
+const RAYDIUM_PROGRAM_ID_MAIN_7: Pubkey = pubkey!("9KEPoZmtHUrBbhWN1v1KWLMkkvwY6WLtAVUCPRtRjP4z");    // MainNET
+const RAYDIUM_PROGRAM_ID_MAIN_8: Pubkey = pubkey!("FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG");    // MainNET
+const RAYDIUM_PROGRAM_ID_MAIN_9: Pubkey = pubkey!("9HzJyW1qZsEiSfMUf6L2jo3CcTKAyBmSyKdwQeYisHrC");    // MainNET
+const ORCA_PROGRAM_ID_MAIN_1: Pubkey = pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");    // MainNET
+
+// Helper function to determine if a transaction is associated with a DEX
+fn is_dex_transaction(source_program_id: &Pubkey, destination_program_id: &Pubkey) -> bool {
+    // Known DEX program IDs
+    const KNOWN_DEX_PROGRAMS: [&Pubkey; 19] = [
+        &SERUM_DEX_PROGRAM_ID_DEV_1,
+        &SERUM_DEX_PROGRAM_ID_MAIN_1,
+        &RAYDIUM_PROGRAM_ID_DEV_1,
+        &RAYDIUM_PROGRAM_ID_DEV_2,
+        &RAYDIUM_PROGRAM_ID_DEV_3,
+        &RAYDIUM_PROGRAM_ID_DEV_4,
+        &RAYDIUM_PROGRAM_ID_DEV_5,
+        &RAYDIUM_PROGRAM_ID_DEV_6,
+        &RAYDIUM_PROGRAM_ID_DEV_7,
+        &RAYDIUM_PROGRAM_ID_MAIN_1,
+        &RAYDIUM_PROGRAM_ID_MAIN_2,
+        &RAYDIUM_PROGRAM_ID_MAIN_3,
+        &RAYDIUM_PROGRAM_ID_MAIN_4,
+        &RAYDIUM_PROGRAM_ID_MAIN_5,
+        &RAYDIUM_PROGRAM_ID_MAIN_6,
+        &RAYDIUM_PROGRAM_ID_MAIN_7,
+        &RAYDIUM_PROGRAM_ID_MAIN_8,
+        &RAYDIUM_PROGRAM_ID_MAIN_9,
+        &ORCA_PROGRAM_ID_MAIN_1,
+    ];
+
+    // Check if source or destination is a known DEX program
+    KNOWN_DEX_PROGRAMS.contains(&source_program_id) || KNOWN_DEX_PROGRAMS.contains(&destination_program_id)
+}
+
+
+// =====================================================Events============================================
+
+#[event]
+pub struct InvestorLocked {
+    pub lock_pool: Pubkey,
+    pub user_wallet: Pubkey,
+    pub amount: u64,                    // Tokens locked by this operation
+    pub user_total_tokens: u64,         // UserLockInfo.total_tokens after the operation
+    pub user_locked_tokens: u64,        // UserLockInfo.locked_tokens after the operation
+    pub total_locked: u64,              // LockPool.total_locked after the operation
+    pub holder_count: u64,              // LockPool.holder_count after the operation
+}
+
+#[event]
+pub struct AllocationRootCommitted {
+    pub lock_pool: Pubkey,
+    pub root: [u8; 32],                 // Merkle root of (wallet, amount, category) leaves
+    pub total_amount: u64,              // Tokens funded for the whole allocation list
+    pub total_locked: u64,              // LockPool.total_locked after the commit
+}
+
+#[event]
+pub struct InvestorMerged {
+    pub lock_pool: Pubkey,
+    pub user_wallet: Pubkey,
+    pub batch_entries: u32,             // `users` entries of the batch summed for this wallet
+    pub existing_record: bool,          // Merged into a UserLockInfo created by an earlier batch
+    pub amount: u64,                    // Tokens added by this batch
+    pub user_total_tokens: u64,         // UserLockInfo.total_tokens after the merge
+}
+
+#[event]
+pub struct DustReclaimed {
+    pub lock_pool: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,                    // Surplus swept by this call
+    pub total_reclaimed: u64,           // LockPool.dust_reclaimed after the sweep
+}
+
+#[event]
+pub struct AdminProposed {
+    pub lock_pool: Pubkey,
+    pub admin_wallet: Pubkey,
+    pub pending_admin_wallet: Pubkey,   // Default when the proposal is cancelled
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub lock_pool: Pubkey,
+    pub previous_admin_wallet: Pubkey,
+    pub admin_wallet: Pubkey,
+}
+
+#[event]
+pub struct RolesUpdated {
+    pub lock_pool: Pubkey,
+    pub milestone_keeper: Pubkey,
+    pub treasury: Pubkey,
+    pub pauser: Pubkey,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct ProposalApproved {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub signer: Pubkey,
+    pub approvals: u8,                  // Distinct approvals so far
+    pub threshold: u8,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub action: ProposalAction,
+}
+
+#[event]
+pub struct ProposalClosed {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub status: ProposalStatus,         // Cancelled or Expired
+}
+
+#[event]
+pub struct ChangeQueued {
+    pub lock_pool: Pubkey,
+    pub queued_change: Pubkey,
+    pub change: ConfigChange,
+    pub eta: i64,                       // Earliest execution time
+}
+
+#[event]
+pub struct ChangeExecuted {
+    pub lock_pool: Pubkey,
+    pub queued_change: Pubkey,
+}
+
+#[event]
+pub struct ChangeCancelled {
+    pub lock_pool: Pubkey,
+    pub queued_change: Pubkey,
+}
+
+#[event]
+pub struct PoolMigrated {
+    pub lock_pool: Pubkey,
+    pub legacy_layout: LegacyLayout,    // Layout the account was converted from
+    pub holder_count: u64,              // UserLockInfo records created from the legacy user list
+    pub total_locked: u64,              // Recomputed from the holders' locked tokens
+    pub legacy_total_locked: u64,       // `total_locked` stored in the legacy account
+    pub total_locked_drift: i128,       // `legacy_total_locked - total_locked` (legacy unlocks and purchases never updated it)
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub lock_pool: Pubkey,
+    pub paused_flags: u8,               // LockPool.paused_flags after the update
+}
+
+#[event]
+pub struct MilestoneAdvanced {
+    pub lock_pool: Pubkey,
+    pub milestone: u8,                  // New current milestone
+    pub percentage: u8,                 // Cumulative unlock percentage of the milestone (Presale schedule)
+    pub market_cap: u64,                // Market cap observation that opened the milestone
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensUnlocked {
+    pub lock_pool: Pubkey,
+    pub user_wallet: Pubkey,
+    pub amount: u64,                    // Tokens transferred out of the lock pool
+    pub user_unlocked_tokens: u64,      // UserLockInfo.unlocked_tokens after the transfer
+    pub user_locked_tokens: u64,        // UserLockInfo.locked_tokens after the transfer
+}
+
+#[event]
+pub struct FullUnlockExecuted {
+    pub lock_pool: Pubkey,
+    pub holder_count: u64,
+    pub total_locked: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnlockJobAborted {
+    pub lock_pool: Pubkey,
+    pub pending_milestone: u8,          // Milestone rolled back (0 for the full unlock)
+    pub pending_full_unlock: bool,
+    pub unlock_cursor: u64,             // First holder the job did not reach
+    pub holder_count: u64,
+}
+
+#[event]
+pub struct TokensPurchased {
+    pub lock_pool: Pubkey,
+    pub user_wallet: Pubkey,
+    pub total_paid_tokens: u64,
+    pub unlocked_tokens: u64,           // Sent to the buyer immediately
+    pub locked_tokens: u64,             // Added to the buyer's lock record
+    pub user_total_tokens: u64,         // UserLockInfo.total_tokens after the purchase
+    pub user_locked_tokens: u64,        // UserLockInfo.locked_tokens after the purchase
+    pub holder_count: u64,              // LockPool.holder_count after the purchase
+}
+
+#[event]
+pub struct TaxCollected {
+    pub lock_pool: Pubkey,
+    pub user_wallet: Pubkey,
+    pub transfer_amount: u64,
+    pub tax: u64,
+    pub burn_amount: u64,
+    pub marketing_amount: u64,
+    pub net_transfer: u64,
+}
+
+#[event]
+pub struct AutoSellExecuted {
+    pub lock_pool: Pubkey,
+    pub project_wallet: Pubkey,
+    pub dex_liquidity_wallet: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+// =====================================================Error=============================================
+
+
+#[error_code]
+pub enum CustomError {
+    #[msg("Unauthorized: You do not have permission to call this method.")]
+    Unauthorized,
+    #[msg("Milestone has not been reached for token unlock.")]
+    MilestoneNotReached,
+    #[msg("Conditions not met: Final milestone or 3-month unlock period.")]
+    UnlockTooSoon,
+    #[msg("Invalid Token Amount")]
+    InvalidTokenAmount,
+    #[msg("Insufficient Pool Balance")]
+    InsufficientPoolBalance,
+    #[msg("Max hold amount exceeded")]
+    MaxHoldExceeded,
+    #[msg("Already Full Unlocked")]
+    FullUnlockAlreadyExecuted,
+    #[msg("Lock accounts have already been initialized.")]
+    AlreadyInitialized,
+    #[msg("Remaining accounts do not match the investor batch")]
+    UserLockAccountsMismatch,
+    #[msg("Invalid UserLockInfo account for this pool")]
+    InvalidUserLockAccount,
+    #[msg("No unlocked tokens to claim")]
+    NothingToClaim,
+    #[msg("No unlock job is in progress")]
+    NoUnlockInProgress,
+    #[msg("Unlock batch does not start at the persisted cursor")]
+    BatchOutOfOrder,
+    #[msg("Invalid pool config: thresholds must increase and rates stay within bounds")]
+    InvalidPoolConfig,
+    #[msg("Price feed is not usable: wrong account, owner, layout or status")]
+    InvalidPriceFeed,
+    #[msg("Manual price feeds are only available in localnet builds")]
+    ManualPriceFeedDisabled,
+    #[msg("Price feed is stale")]
+    StalePrice,
+    #[msg("Price confidence interval is too wide")]
+    PriceConfidenceTooWide,
+    #[msg("Token account mint does not match")]
+    InvalidMint,
+    #[msg("AMM pool is not usable: wrong account, owner, layout, vaults or reserves")]
+    InvalidAmmPool,
+    #[msg("Market cap computation overflowed")]
+    MarketCapOverflow,
+    #[msg("Allocation root has already been committed")]
+    AllocationRootAlreadySet,
+    #[msg("Allocation proof does not match the committed root")]
+    InvalidAllocationProof,
+    #[msg("Allocation has already been registered for this wallet")]
+    AllocationAlreadyRegistered,
+    #[msg("Pool is sealed; the investor list is immutable")]
+    PoolSealed,
+    #[msg("Pool has not been sealed yet")]
+    PoolNotSealed,
+    #[msg("Vault balance does not match total locked tokens")]
+    VaultBalanceMismatch,
+    #[msg("Wallet is already registered under another investor category")]
+    InvestorCategoryMismatch,
+    #[msg("Sum of user locked tokens does not match total locked tokens")]
+    LockedSumMismatch,
+    #[msg("Token account is not the pool's lock vault")]
+    InvalidVault,
+    #[msg("Recipient token account is not the wallet's associated token account for the pool mint")]
+    InvalidRecipientTokenAccount,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Unlock exceeds the locked balance")]
+    UnlockUnderflow,
+    #[msg("Pool already has a multisig")]
+    MultisigAlreadySet,
+    #[msg("Invalid multisig signers, threshold or proposal")]
+    InvalidMultisig,
+    #[msg("Signer is not a member of the multisig")]
+    NotMultisigSigner,
+    #[msg("An approved multisig proposal is required")]
+    ProposalRequired,
+    #[msg("Proposal is not active")]
+    ProposalNotActive,
+    #[msg("Proposal has expired")]
+    ProposalExpired,
+    #[msg("Proposal does not authorize this instruction")]
+    ProposalActionMismatch,
+    #[msg("Proposal has not reached the approval threshold")]
+    ProposalNotApproved,
+    #[msg("Timelock delay is below the minimum")]
+    InvalidTimelockDelay,
+    #[msg("Change is not queued for this pool")]
+    ChangeNotQueued,
+    #[msg("Timelock has not elapsed")]
+    TimelockNotElapsed,
+    #[msg("Operation is paused")]
+    Paused,
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+    #[msg("Unsupported LockPool layout version")]
+    UnsupportedVersion,
+    #[msg("Account does not match the legacy layout")]
+    InvalidLegacyLayout,
+    #[msg("Not every legacy holder has been migrated yet")]
+    MigrationIncomplete,
+    #[msg("Pool has reached its holder limit")]
+    PoolFull,
+    #[msg("Holder limit must be greater than zero and cover the existing holders")]
+    InvalidMaxHolders,
+    #[msg("The pool config account is required for this change")]
+    PoolConfigRequired,
+    #[msg("Pool totals changed since the audit started; restart it from record 0")]
+    AuditOutdated,
+    #[msg("An unlock job is already in progress")]
+    UnlockInProgress,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    fn assert_error<T>(result: Result<T>, expected: CustomError) {
+        match result {
+            Ok(_) => panic!("expected {expected:?}"),
+            Err(error) => assert_eq!(error, Error::from(expected)),
+        }
+    }
+
+    // =============================================AMM fixtures=============================================
+
+    struct AmmFixture {
+        token_mint: Pubkey,
+        quote_mint: Pubkey,
+        token_vault: Pubkey,
+        quote_vault: Pubkey,
+    }
+
+    impl AmmFixture {
+        fn new() -> Self {
+            Self {
+                token_mint: Pubkey::new_unique(),
+                quote_mint: Pubkey::new_unique(),
+                token_vault: Pubkey::new_unique(),
+                quote_vault: Pubkey::new_unique(),
+            }
+        }
+    }
+
+    // Serialized Raydium CPMM PoolState with the pool token as token_0 (or token_1) and fees per side
+    fn raydium_cpmm_pool(fixture: &AmmFixture, token_is_0: bool, fees_0: [u64; 2], fees_1: [u64; 2]) -> Vec<u8> {
+        let mut data = vec![0u8; RAYDIUM_CPMM_POOL_MIN_LEN];
+        data[..8].copy_from_slice(&RAYDIUM_CPMM_POOL_DISCRIMINATOR);
+        let (vault_0, vault_1, mint_0, mint_1) = if token_is_0 {
+            (fixture.token_vault, fixture.quote_vault, fixture.token_mint, fixture.quote_mint)
+        } else {
+            (fixture.quote_vault, fixture.token_vault, fixture.quote_mint, fixture.token_mint)
+        };
+        data[72..104].copy_from_slice(vault_0.as_ref());
+        data[104..136].copy_from_slice(vault_1.as_ref());
+        data[168..200].copy_from_slice(mint_0.as_ref());
+        data[200..232].copy_from_slice(mint_1.as_ref());
+        data[341..349].copy_from_slice(&fees_0[0].to_le_bytes()); // protocol_fees_token_0
+        data[349..357].copy_from_slice(&fees_1[0].to_le_bytes()); // protocol_fees_token_1
+        data[357..365].copy_from_slice(&fees_0[1].to_le_bytes()); // fund_fees_token_0
+        data[365..373].copy_from_slice(&fees_1[1].to_le_bytes()); // fund_fees_token_1
+        data
+    }
+
+    // Serialized Orca Whirlpool with the pool token as token A (or token B)
+    fn orca_whirlpool(fixture: &AmmFixture, token_is_a: bool, sqrt_price: u128) -> Vec<u8> {
+        let mut data = vec![0u8; ORCA_WHIRLPOOL_MIN_LEN];
+        data[..8].copy_from_slice(&ORCA_WHIRLPOOL_DISCRIMINATOR);
+        data[65..81].copy_from_slice(&sqrt_price.to_le_bytes());
+        let (mint_a, vault_a, mint_b, vault_b) = if token_is_a {
+            (fixture.token_mint, fixture.token_vault, fixture.quote_mint, fixture.quote_vault)
+        } else {
+            (fixture.quote_mint, fixture.quote_vault, fixture.token_mint, fixture.token_vault)
+        };
+        data[101..133].copy_from_slice(mint_a.as_ref());
+        data[133..165].copy_from_slice(vault_a.as_ref());
+        data[181..213].copy_from_slice(mint_b.as_ref());
+        data[213..245].copy_from_slice(vault_b.as_ref());
+        data
+    }
+
+    fn parse_cpmm(fixture: &AmmFixture, data: &[u8]) -> Result<CpmmFees> {
+        parse_raydium_cpmm_pool(data, &fixture.token_vault, &fixture.quote_vault, &fixture.token_mint, &fixture.quote_mint)
+    }
+
+    fn parse_whirlpool(fixture: &AmmFixture, data: &[u8]) -> Result<SpotPrice> {
+        parse_orca_whirlpool(data, &fixture.token_vault, &fixture.quote_vault, &fixture.token_mint, &fixture.quote_mint)
+    }
+
+    // =============================================AMM tests================================================
+
+    #[test]
+    fn raydium_cpmm_fees_follow_token_orientation() {
+        let fixture = AmmFixture::new();
+
+        let fees = parse_cpmm(&fixture, &raydium_cpmm_pool(&fixture, true, [10, 5], [20, 7])).unwrap();
+        assert_eq!((fees.token_fees, fees.quote_fees), (15, 27));
+
+        let fees = parse_cpmm(&fixture, &raydium_cpmm_pool(&fixture, false, [10, 5], [20, 7])).unwrap();
+        assert_eq!((fees.token_fees, fees.quote_fees), (27, 15));
+    }
+
+    #[test]
+    fn raydium_cpmm_rejects_foreign_vaults_mints_and_short_data() {
+        let fixture = AmmFixture::new();
+        let data = raydium_cpmm_pool(&fixture, true, [0, 0], [0, 0]);
+
+        let wrong_vault = parse_raydium_cpmm_pool(
+            &data,
+            &Pubkey::new_unique(),
+            &fixture.quote_vault,
+            &fixture.token_mint,
+            &fixture.quote_mint,
+        );
+        assert_error(wrong_vault, CustomError::InvalidAmmPool);
+
+        let wrong_mint = parse_raydium_cpmm_pool(
+            &data,
+            &fixture.token_vault,
+            &fixture.quote_vault,
+            &Pubkey::new_unique(),
+            &fixture.quote_mint,
+        );
+        assert_error(wrong_mint, CustomError::InvalidAmmPool);
+
+        // Vaults swapped against the mints
+        let swapped = parse_raydium_cpmm_pool(
+            &data,
+            &fixture.quote_vault,
+            &fixture.token_vault,
+            &fixture.token_mint,
+            &fixture.quote_mint,
+        );
+        assert_error(swapped, CustomError::InvalidAmmPool);
+
+        assert_error(parse_cpmm(&fixture, &data[..RAYDIUM_CPMM_POOL_MIN_LEN - 1]), CustomError::InvalidAmmPool);
+
+        let mut bad_discriminator = data.clone();
+        bad_discriminator[0] ^= 1;
+        assert_error(parse_cpmm(&fixture, &bad_discriminator), CustomError::InvalidAmmPool);
+    }
+
+    #[test]
+    fn orca_whirlpool_detects_token_side() {
+        let fixture = AmmFixture::new();
+        let sqrt_price = 3u128 << 64;
+
+        let spot = parse_whirlpool(&fixture, &orca_whirlpool(&fixture, true, sqrt_price)).unwrap();
+        assert!(matches!(spot, SpotPrice::SqrtPriceX64 { sqrt_price: s, token_is_a: true } if s == sqrt_price));
+
+        let spot = parse_whirlpool(&fixture, &orca_whirlpool(&fixture, false, sqrt_price)).unwrap();
+        assert!(matches!(spot, SpotPrice::SqrtPriceX64 { sqrt_price: s, token_is_a: false } if s == sqrt_price));
+    }
+
+    #[test]
+    fn orca_whirlpool_rejects_foreign_vaults_mints_and_short_data() {
+        let fixture = AmmFixture::new();
+        let data = orca_whirlpool(&fixture, true, 1u128 << 64);
+
+        let wrong_vault = parse_orca_whirlpool(
+            &data,
+            &fixture.token_vault,
+            &Pubkey::new_unique(),
+            &fixture.token_mint,
+            &fixture.quote_mint,
+        );
+        assert_error(wrong_vault, CustomError::InvalidAmmPool);
+
+        let wrong_mint = parse_orca_whirlpool(
+            &data,
+            &fixture.token_vault,
+            &fixture.quote_vault,
+            &fixture.token_mint,
+            &Pubkey::new_unique(),
+        );
+        assert_error(wrong_mint, CustomError::InvalidAmmPool);
+
+        assert_error(parse_whirlpool(&fixture, &data[..ORCA_WHIRLPOOL_MIN_LEN - 1]), CustomError::InvalidAmmPool);
+        assert_error(parse_whirlpool(&fixture, &orca_whirlpool(&fixture, true, 0)), CustomError::InvalidAmmPool);
+    }
+
+    #[test]
+    fn market_cap_from_reserves() {
+        // 2 raw quote units per raw token, 1_000_000 tokens circulating, 6 quote decimals
+        let spot = SpotPrice::Reserves { token_reserve: 1_000, quote_reserve: 2_000 };
+        assert_eq!(market_cap_from_spot_price(1_000_000_000_000, 6, &spot).unwrap(), 2_000_000);
+
+        let empty = SpotPrice::Reserves { token_reserve: 0, quote_reserve: 2_000 };
+        assert_error(market_cap_from_spot_price(1, 6, &empty), CustomError::InvalidAmmPool);
+    }
+
+    #[test]
+    fn market_cap_from_sqrt_price_in_both_orientations() {
+        let sqrt_price = 2u128 << 64; // b per a = 4
+
+        let token_a = SpotPrice::SqrtPriceX64 { sqrt_price, token_is_a: true };
+        assert_eq!(market_cap_from_spot_price(1_000_000, 0, &token_a).unwrap(), 4_000_000);
+
+        let token_b = SpotPrice::SqrtPriceX64 { sqrt_price, token_is_a: false };
+        assert_eq!(market_cap_from_spot_price(1_000_000, 0, &token_b).unwrap(), 250_000);
+
+        // Quote decimals scale the result down
+        assert_eq!(market_cap_from_spot_price(1_000_000, 3, &token_a).unwrap(), 4_000);
+    }
+
+    #[test]
+    fn market_cap_saturates_and_overflows_explicitly() {
+        let huge = SpotPrice::Reserves { token_reserve: 1, quote_reserve: u64::MAX };
+        assert_eq!(market_cap_from_spot_price(u64::MAX, 0, &huge).unwrap(), u64::MAX);
+
+        let max_sqrt = SpotPrice::SqrtPriceX64 { sqrt_price: u128::MAX, token_is_a: true };
+        assert_error(market_cap_from_spot_price(u64::MAX, 0, &max_sqrt), CustomError::MarketCapOverflow);
+    }
+
+    #[test]
+    fn mul_shr_64_matches_wide_product() {
+        let one = 1u128 << 64;
+        assert_eq!(mul_shr_64(one, one), Some(one));
+        assert_eq!(mul_shr_64(12_345, one), Some(12_345));
+        assert_eq!(mul_shr_64(3, 5), Some(0)); // Sub-unit products floor to zero
+        assert_eq!(mul_shr_64((3 << 64) + 5, 7), Some(21));
+        assert_eq!(mul_shr_64(u128::MAX, one), Some(u128::MAX));
+        assert_eq!(mul_shr_64(u128::MAX, u128::MAX), None);
+        assert_eq!(mul_shr_64(u128::MAX, 2 * one), None);
+    }
+
+    // =============================================Migration fixtures=======================================
+
+    fn legacy_user(user_wallet: Pubkey, total_tokens: u64, unlocked_tokens: u64) -> LegacyUserLockInfo {
+        LegacyUserLockInfo {
+            user_wallet,
+            total_tokens,
+            unlocked_tokens,
+            locked_tokens: total_tokens - unlocked_tokens,
+        }
+    }
+
+    // Legacy account data: discriminator, Borsh payload, then `padding` zero bytes of unused space
+    fn legacy_account<T: AnchorSerialize>(pool: &T, padding: usize) -> Vec<u8> {
+        let mut data = LEGACY_LOCK_POOL_DISCRIMINATOR.to_vec();
+        pool.serialize(&mut data).unwrap();
+        data.resize(data.len() + padding, 0);
+        data
+    }
+
+    // Zeroed current-layout account data carrying the given version byte
+    fn versioned_account(version: u8) -> Vec<u8> {
+        let mut data = vec![0u8; 8 + LockPool::INIT_SPACE];
+        data[..8].copy_from_slice(&LockPool::DISCRIMINATOR);
+        data[8] = version;
+        data
+    }
+
+    // =============================================Migration tests==========================================
+
+    #[test]
+    fn read_legacy_pool_decodes_original_layout() {
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let data = legacy_account(
+            &LegacyLockPoolOriginal {
+                total_locked: 110,
+                users: vec![legacy_user(alice, 100, 40), legacy_user(bob, 50, 0)],
+            },
+            64,
+        );
+
+        let legacy = read_legacy_pool(&data, LegacyLayout::Original).unwrap();
+        assert_eq!(legacy.total_locked, 110);
+        assert_eq!(legacy.users.len(), 2);
+        assert_eq!(legacy.users[0].user_wallet, alice);
+        assert_eq!(legacy.users[0].locked_tokens, 60);
+        assert_eq!(legacy.users[1].user_wallet, bob);
+        // Fields the original layout lacks take their pre-milestone defaults
+        assert_eq!(legacy.start_time, 0);
+        assert_eq!(legacy.current_milestone, 0);
+        assert!(!legacy.full_unlock_executed);
+        assert!(legacy.is_max_hold_limit_active);
+    }
+
+    #[test]
+    fn read_legacy_pool_decodes_with_milestones_layout() {
+        let alice = Pubkey::new_unique();
+        let data = legacy_account(
+            &LegacyLockPoolWithMilestones {
+                total_locked: 75,
+                users: vec![legacy_user(alice, 100, 25)],
+                start_time: 1_700_000_000,
+                current_milestone: 3,
+                full_unlock_executed: false,
+                is_max_hold_limit_active: false,
+            },
+            0,
+        );
+
+        let legacy = read_legacy_pool(&data, LegacyLayout::WithMilestones).unwrap();
+        assert_eq!(legacy.total_locked, 75);
+        assert_eq!(legacy.users[0].unlocked_tokens, 25);
+        assert_eq!(legacy.start_time, 1_700_000_000);
+        assert_eq!(legacy.current_milestone, 3);
+        assert!(!legacy.is_max_hold_limit_active);
+    }
+
+    #[test]
+    fn read_legacy_pool_rejects_trailing_garbage_and_wrong_layout() {
+        let alice = Pubkey::new_unique();
+        let original = LegacyLockPoolOriginal { total_locked: 100, users: vec![legacy_user(alice, 100, 0)] };
+
+        let mut garbage = legacy_account(&original, 16);
+        garbage.push(7);
+        assert_error(read_legacy_pool(&garbage, LegacyLayout::Original), CustomError::InvalidLegacyLayout);
+
+        // The milestone fields left over after an Original decode are not padding
+        let with_milestones = legacy_account(
+            &LegacyLockPoolWithMilestones::from(LegacyLockPoolOriginal {
+                total_locked: 100,
+                users: vec![legacy_user(alice, 100, 0)],
+            }),
+            0,
+        );
+        assert_error(read_legacy_pool(&with_milestones, LegacyLayout::Original), CustomError::InvalidLegacyLayout);
+
+        // An Original account is too short to hold the milestone fields
+        let unpadded = legacy_account(&original, 0);
+        assert_error(read_legacy_pool(&unpadded, LegacyLayout::WithMilestones), CustomError::InvalidLegacyLayout);
+    }
+
+    #[test]
+    fn read_legacy_pool_rejects_migrated_and_foreign_accounts() {
+        let migrated = versioned_account(LOCK_POOL_VERSION);
+        assert_error(read_legacy_pool(&migrated, LegacyLayout::Original), CustomError::AlreadyInitialized);
+        assert_error(read_legacy_pool(&migrated, LegacyLayout::WithMilestones), CustomError::AlreadyInitialized);
+
+        let mut foreign = legacy_account(&LegacyLockPoolOriginal { total_locked: 0, users: vec![] }, 0);
+        foreign[0] ^= 1;
+        assert_error(read_legacy_pool(&foreign, LegacyLayout::Original), CustomError::InvalidLegacyLayout);
+        assert_error(read_legacy_pool(&foreign[..4], LegacyLayout::Original), CustomError::InvalidLegacyLayout);
+    }
+
+    #[test]
+    fn merge_legacy_users_sums_repeated_wallets_in_order() {
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let holders = merge_legacy_users(&[
+            legacy_user(bob, 10, 0),
+            legacy_user(alice, 100, 40),
+            legacy_user(bob, 30, 5),
+        ])
+        .unwrap();
+
+        assert_eq!(holders.len(), 2);
+        assert_eq!(holders[0].user_wallet, bob);
+        assert_eq!((holders[0].total_tokens, holders[0].unlocked_tokens, holders[0].locked_tokens), (40, 5, 35));
+        assert_eq!(holders[1].user_wallet, alice);
+        assert_eq!((holders[1].total_tokens, holders[1].unlocked_tokens, holders[1].locked_tokens), (100, 40, 60));
+    }
+
+    #[test]
+    fn merge_legacy_users_rejects_inconsistent_entries() {
+        let mut broken = legacy_user(Pubkey::new_unique(), 100, 40);
+        broken.locked_tokens = 61;
+        assert_error(merge_legacy_users(&[broken]), CustomError::LockedSumMismatch);
+
+        let overflowing = LegacyUserLockInfo {
+            user_wallet: Pubkey::new_unique(),
+            total_tokens: 1,
+            unlocked_tokens: u64::MAX,
+            locked_tokens: 2,
+        };
+        assert_error(merge_legacy_users(&[overflowing]), CustomError::LockedSumMismatch);
+    }
+
+    #[test]
+    fn lock_pool_only_deserializes_current_version() {
+        let current = versioned_account(LOCK_POOL_VERSION);
+        let lock_pool = LockPool::try_deserialize(&mut &current[..]).unwrap();
+        assert_eq!(lock_pool.version, LOCK_POOL_VERSION);
+
+        let mut round_trip = Vec::new();
+        lock_pool.try_serialize(&mut round_trip).unwrap();
+        assert_eq!(round_trip, current);
+
+        for version in [0, 1, LOCK_POOL_VERSION + 1] {
+            let data = versioned_account(version);
+            assert_error(LockPool::try_deserialize(&mut &data[..]), CustomError::UnsupportedVersion);
+        }
+    }
+
+    #[test]
+    fn legacy_pool_whose_total_looks_like_a_version_is_not_a_lock_pool() {
+        // The first payload byte of a legacy account is the low byte of `total_locked`
+        let users = (0..12).map(|_| legacy_user(Pubkey::new_unique(), 1, 1)).collect();
+        let data = legacy_account(&LegacyLockPoolOriginal { total_locked: LOCK_POOL_VERSION as u64, users }, 0);
+        assert_eq!(data[8], LOCK_POOL_VERSION);
+        assert!(data.len() >= 8 + LockPool::INIT_SPACE);
+
+        assert_eq!(
+            LockPool::try_deserialize(&mut &data[..]).err(),
+            Some(Error::from(ErrorCode::AccountDiscriminatorMismatch))
+        );
+        let legacy = read_legacy_pool(&data, LegacyLayout::Original).unwrap();
+        assert_eq!(legacy.total_locked, 2);
+        assert_eq!(legacy.users.len(), 12);
+    }
+
+    #[test]
+    fn lock_pool_size_is_pinned_to_its_version() {
+        assert_eq!(
+            LockPool::INIT_SPACE,
+            475,
+            "LockPool layout changed: bump LOCK_POOL_VERSION and migrate version {LOCK_POOL_VERSION} pools"
+        );
+    }
+
+    #[test]
+    fn discriminators_match_account_namespaces() {
+        let hash = anchor_lang::solana_program::hash::hash(b"account:LockPool");
+        assert_eq!(LockPool::DISCRIMINATOR, hash.to_bytes()[..8]);
+        let legacy_hash = anchor_lang::solana_program::hash::hash(b"account:LockPoolState");
+        assert_eq!(LEGACY_LOCK_POOL_DISCRIMINATOR, legacy_hash.to_bytes()[..8]);
+    }
+
+    fn migrated_pool_settings(max_holders: u64) -> MigratedPoolSettings {
+        MigratedPoolSettings {
+            mint: Pubkey::new_unique(),
+            admin_wallet: Pubkey::new_unique(),
+            lock_pool_token_account: Pubkey::new_unique(),
+            token_pool_account: Pubkey::new_unique(),
+            vault_authority_bump: 254,
+            timelock_delay: MIN_TIMELOCK_DELAY,
+            max_holders,
+        }
+    }
+
+    #[test]
+    fn migration_recomputes_drifted_total_locked() {
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // The legacy total still counts Alice's 600 unlocked tokens and misses Carol's purchase
+        let carol = Pubkey::new_unique();
+        let data = legacy_account(
+            &LegacyLockPoolWithMilestones {
+                total_locked: 1_700,
+                users: vec![
+                    legacy_user(alice, 1_000, 600),
+                    legacy_user(bob, 500, 0),
+                    legacy_user(alice, 200, 0),
+                    legacy_user(carol, 300, 0),
+                ],
+                start_time: 1_700_000_000,
+                current_milestone: 2,
+                full_unlock_executed: false,
+                is_max_hold_limit_active: true,
+            },
+            32,
+        );
+        let legacy = read_legacy_pool(&data, LegacyLayout::WithMilestones).unwrap();
+        let holders = merge_legacy_users(&legacy.users).unwrap();
+
+        // Holders are recreated over two `migrate_pool_holders` batches
+        let mut migration = PoolMigration {
+            lock_pool: Pubkey::new_unique(),
+            legacy_layout: LegacyLayout::WithMilestones,
+            holder_cursor: 0,
+            total_locked: 0,
+            bump: 255,
+        };
+        assert_eq!(migration.record_holder(&holders[0]).unwrap(), 0);
+        assert_error(
+            convert_legacy_pool(&legacy, &migration, migrated_pool_settings(10)),
+            CustomError::MigrationIncomplete,
+        );
+        assert_eq!(migration.record_holder(&holders[1]).unwrap(), 1);
+        assert_eq!(migration.record_holder(&holders[2]).unwrap(), 2);
+        assert_error(
+            convert_legacy_pool(&legacy, &migration, migrated_pool_settings(2)),
+            CustomError::PoolFull,
+        );
+
+        let settings = migrated_pool_settings(3);
+        let admin_wallet = settings.admin_wallet;
+        let lock_pool = convert_legacy_pool(&legacy, &migration, settings).unwrap();
+        assert_eq!(lock_pool.total_locked, 400 + 200 + 500 + 300);
+        assert_ne!(lock_pool.total_locked, legacy.total_locked);
+        assert_eq!(lock_pool.holder_count, 3);
+        assert_eq!(lock_pool.phase, PoolPhase::Loading);
+        assert_eq!((lock_pool.start_time, lock_pool.current_milestone), (1_700_000_000, 2));
+        assert_eq!(lock_pool.treasury, admin_wallet);
+        assert_eq!(lock_pool.max_holders, 3);
+
+        // The rewritten account loads as a LockPool and is never migrated again
+        let mut rewritten = Vec::new();
+        lock_pool.try_serialize(&mut rewritten).unwrap();
+        assert_eq!(LockPool::try_deserialize(&mut &rewritten[..]).unwrap().total_locked, 1_400);
+        assert_error(read_legacy_pool(&rewritten, LegacyLayout::WithMilestones), CustomError::AlreadyInitialized);
+    }
+
+    // =============================================Vesting fixtures=========================================
+
+    // Pool rules where every category follows `percentages` with linear vesting disabled
+    fn milestone_only_config(percentages: [u8; MILESTONE_COUNT]) -> PoolConfig {
+        let schedule = VestingSchedule { milestone_percentages: percentages, cliff: 0, duration: 0 };
+        PoolConfig {
+            lock_pool: Pubkey::new_unique(),
+            milestone_thresholds: [1, 2, 3, 4, 5, 6, 7, 8],
+            schedules: [schedule; CATEGORY_COUNT],
+            tax_bps: 0,
+            burn_share_bps: 0,
+            max_hold_amount: u64::MAX,
+            full_unlock_delay: 0,
+            auto_sell_bps: 0,
+            price_feed: Pubkey::new_unique(),
+            price_feed_id: [0; 32],
+            max_price_age: 0,
+            max_confidence_bps: 0,
+            milestone_hold_secs: 0,
+            amm_pool: Pubkey::default(),
+            bump: 0,
+        }
+    }
+
+    fn sealed_pool_at(current_milestone: u8) -> LockPool {
+        let data = versioned_account(LOCK_POOL_VERSION);
+        let mut lock_pool = LockPool::try_deserialize(&mut &data[..]).unwrap();
+        lock_pool.phase = PoolPhase::Sealed;
+        lock_pool.current_milestone = current_milestone;
+        lock_pool
+    }
+
+    fn presale_record() -> UserLockInfo {
+        UserLockInfo {
+            lock_pool: Pubkey::new_unique(),
+            index: 0,
+            user_wallet: Pubkey::new_unique(),
+            category: InvestorCategory::Presale,
+            total_tokens: 0,
+            unlocked_tokens: 0,
+            locked_tokens: 0,
+            allocation_registered: false,
+            bump: 0,
+        }
+    }
+
+    // =============================================Vesting tests============================================
+
+    #[test]
+    fn purchase_then_claim_pays_only_newly_vested_tokens() {
+        let pool_config = milestone_only_config([50, 60, 70, 80, 90, 95, 100, 100]);
+        let mut lock_pool = sealed_pool_at(1);
+        let mut user = presale_record();
+
+        // Buying 1000 at 50% transfers 500 up front and locks the other 500
+        user.add_purchase(1000, 500).unwrap();
+        assert_eq!((user.total_tokens, user.unlocked_tokens, user.locked_tokens), (1000, 500, 500));
+        assert_eq!(claimable_amount(&pool_config, &lock_pool, &user, 0).unwrap(), 0);
+
+        // The next milestone releases its extra 10% of the full purchase
+        lock_pool.current_milestone = 2;
+        assert_eq!(claimable_amount(&pool_config, &lock_pool, &user, 0).unwrap(), 100);
+
+        // A second purchase at 60% stays consistent with the first
+        user.add_purchase(500, 300).unwrap();
+        assert_eq!((user.total_tokens, user.unlocked_tokens, user.locked_tokens), (1500, 800, 700));
+        assert_eq!(claimable_amount(&pool_config, &lock_pool, &user, 0).unwrap(), 100);
+
+        lock_pool.full_unlock_executed = true;
+        assert_eq!(claimable_amount(&pool_config, &lock_pool, &user, 0).unwrap(), 700);
+    }
+
+    #[test]
+    fn add_purchase_rejects_unlocking_more_than_bought() {
+        let mut user = presale_record();
+        assert_error(user.add_purchase(100, 101), CustomError::UnlockUnderflow);
+    }
+
+    // =============================================Unlock job tests=========================================
+
+    #[test]
+    fn milestone_job_commits_only_after_the_last_holder() {
+        let pool_config = milestone_only_config([10, 20, 30, 40, 50, 60, 70, 100]);
+        let mut lock_pool = sealed_pool_at(1);
+        lock_pool.holder_count = 2;
+        let mut user = presale_record();
+        user.add_purchase(1000, 0).unwrap();
+
+        lock_pool.pending_milestone = 3;
+        start_unlock_job(Pubkey::new_unique(), &mut lock_pool, &pool_config, 0);
+        assert!(lock_pool.unlock_in_progress);
+        assert_eq!(lock_pool.current_milestone, 1);
+
+        // Batches pay the pending milestone; `claim` still sees the committed one
+        assert_eq!(pending_claimable_amount(&pool_config, &lock_pool, &user, 0).unwrap(), 300);
+        assert_eq!(claimable_amount(&pool_config, &lock_pool, &user, 0).unwrap(), 100);
+
+        // No new job opens while this one runs
+        assert_eq!(observe_market_cap(&mut lock_pool, &pool_config, 8, 0), None);
+
+        complete_unlock_job(Pubkey::new_unique(), &mut lock_pool, &pool_config, 0);
+        assert_eq!(lock_pool.current_milestone, 3);
+        assert!(!lock_pool.unlock_in_progress);
+        assert_eq!((lock_pool.pending_milestone, lock_pool.unlock_cursor), (0, 0));
+    }
+
+    #[test]
+    fn aborted_job_rolls_back_the_pending_full_unlock() {
+        let pool_config = milestone_only_config([10, 20, 30, 40, 50, 60, 70, 100]);
+        let mut lock_pool = sealed_pool_at(1);
+        lock_pool.holder_count = 2;
+        let mut paid = presale_record();
+        paid.add_purchase(1000, 0).unwrap();
+        let mut unreached = paid.clone();
+
+        lock_pool.pending_full_unlock = true;
+        start_unlock_job(Pubkey::new_unique(), &mut lock_pool, &pool_config, 0);
+
+        // The first holder is paid in full before the keeper aborts
+        let amount = pending_claimable_amount(&pool_config, &lock_pool, &paid, 0).unwrap();
+        assert_eq!(amount, 1000);
+        paid.release(amount).unwrap();
+        lock_pool.unlock_cursor = 1;
+        close_unlock_job(&mut lock_pool);
+
+        assert!(!lock_pool.full_unlock_executed);
+        assert!(!lock_pool.pending_full_unlock && !lock_pool.unlock_in_progress);
+        assert_eq!(claimable_amount(&pool_config, &lock_pool, &paid, 0).unwrap(), 0);
+        assert_eq!(claimable_amount(&pool_config, &lock_pool, &unreached, 0).unwrap(), 100);
+
+        // A reopened job pays only what each holder has not received yet
+        lock_pool.pending_full_unlock = true;
+        start_unlock_job(Pubkey::new_unique(), &mut lock_pool, &pool_config, 0);
+        assert_eq!(pending_claimable_amount(&pool_config, &lock_pool, &paid, 0).unwrap(), 0);
+        unreached.release(100).unwrap();
+        assert_eq!(pending_claimable_amount(&pool_config, &lock_pool, &unreached, 0).unwrap(), 900);
+    }
+
+    #[test]
+    fn unlock_job_without_holders_commits_immediately() {
+        let pool_config = milestone_only_config([10, 20, 30, 40, 50, 60, 70, 100]);
+        let mut lock_pool = sealed_pool_at(0);
+
+        lock_pool.pending_full_unlock = true;
+        start_unlock_job(Pubkey::new_unique(), &mut lock_pool, &pool_config, 0);
+        assert!(lock_pool.full_unlock_executed && !lock_pool.unlock_in_progress);
+    }
+
+    // =============================================Oracle tests=============================================
+
+    const FEED_ID: [u8; 32] = [7; 32];
+
+    // PriceUpdateV2 account data; `verification_level` is the Borsh-encoded enum (`[1]` = Full, `[0, n]` = Partial)
+    fn price_update(feed_id: [u8; 32], verification_level: &[u8], price: i64, conf: u64, expo: i32, publish_time: i64) -> Vec<u8> {
+        let mut data = PYTH_PRICE_UPDATE_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[0; 32]); // write_authority
+        data.extend_from_slice(verification_level);
+        data.extend_from_slice(&feed_id);
+        data.extend_from_slice(&price.to_le_bytes());
+        data.extend_from_slice(&conf.to_le_bytes());
+        data.extend_from_slice(&expo.to_le_bytes());
+        data.extend_from_slice(&publish_time.to_le_bytes());
+        data.extend_from_slice(&[0; 32]); // prev_publish_time, ema_price, ema_conf, posted_slot
+        data
+    }
+
+    fn oracle_config(max_price_age: i64, max_confidence_bps: u16) -> PoolConfig {
+        let mut pool_config = milestone_only_config([0; MILESTONE_COUNT]);
+        pool_config.price_feed_id = FEED_ID;
+        pool_config.max_price_age = max_price_age;
+        pool_config.max_confidence_bps = max_confidence_bps;
+        pool_config
+    }
+
+    fn price_at(price: i64, conf: u64, publish_time: i64) -> PriceData {
+        PriceData { price, conf, expo: -2, publish_time }
+    }
+
+    #[test]
+    fn parse_pyth_price_reads_a_full_price_update() {
+        let data = price_update(FEED_ID, &[PYTH_VERIFICATION_FULL], 250, 3, -2, 1_700_000_000);
+        assert_eq!(data.len(), PYTH_PRICE_UPDATE_MIN_LEN);
+
+        let price = parse_pyth_price(&data, &FEED_ID).unwrap();
+        assert_eq!((price.price, price.conf, price.expo, price.publish_time), (250, 3, -2, 1_700_000_000));
+    }
+
+    #[test]
+    fn parse_pyth_price_rejects_unverified_foreign_or_malformed_updates() {
+        let full = price_update(FEED_ID, &[PYTH_VERIFICATION_FULL], 250, 3, -2, 0);
+
+        // Partially verified updates are not trusted
+        let partial = price_update(FEED_ID, &[0, 5], 250, 3, -2, 0);
+        assert_error(parse_pyth_price(&partial, &FEED_ID), CustomError::InvalidPriceFeed);
+
+        // A valid update for another feed
+        assert_error(parse_pyth_price(&full, &[8; 32]), CustomError::InvalidPriceFeed);
+
+        // Another account type, or truncated data
+        let mut other = full.clone();
+        other[..8].copy_from_slice(&LockPool::DISCRIMINATOR);
+        assert_error(parse_pyth_price(&other, &FEED_ID), CustomError::InvalidPriceFeed);
+        assert_error(parse_pyth_price(&full[..full.len() - 1], &FEED_ID), CustomError::InvalidPriceFeed);
+    }
+
+    #[test]
+    fn check_price_rejects_stale_prices() {
+        let pool_config = oracle_config(60, BPS_DENOMINATOR as u16);
+
+        assert!(check_price(&price_at(250, 0, 1_000), &pool_config, 1_060).is_ok());
+        assert_error(check_price(&price_at(250, 0, 1_000), &pool_config, 1_061), CustomError::StalePrice);
+    }
+
+    #[test]
+    fn check_price_rejects_wide_confidence_and_non_positive_prices() {
+        let pool_config = oracle_config(60, 100); // 1%
+
+        assert!(check_price(&price_at(10_000, 100, 0), &pool_config, 0).is_ok());
+        assert_error(check_price(&price_at(10_000, 101, 0), &pool_config, 0), CustomError::PriceConfidenceTooWide);
+        assert_error(check_price(&price_at(0, 0, 0), &pool_config, 0), CustomError::InvalidPriceFeed);
+        assert_error(check_price(&price_at(-1, 0, 0), &pool_config, 0), CustomError::InvalidPriceFeed);
+    }
+
+    #[test]
+    fn market_cap_from_price_scales_by_exponent_and_decimals() {
+        // 1M tokens with 6 decimals at 2.50
+        let price = PriceData { price: 250, conf: 0, expo: -2, publish_time: 0 };
+        assert_eq!(market_cap_from_price(1_000_000_000_000, 6, &price).unwrap(), 2_500_000);
+
+        // Positive exponents multiply, and an oversized market cap saturates
+        let price = PriceData { price: 3, conf: 0, expo: 2, publish_time: 0 };
+        assert_eq!(market_cap_from_price(5, 0, &price).unwrap(), 1_500);
+        assert_eq!(market_cap_from_price(u64::MAX, 0, &price).unwrap(), u64::MAX);
+
+        // An exponent beyond u128 range is rejected
+        let price = PriceData { price: 1, conf: 0, expo: 40, publish_time: 0 };
+        assert_error(market_cap_from_price(1, 0, &price), CustomError::InvalidPriceFeed);
+    }
+
+    // =============================================Audit tests==============================================
+
+    #[test]
+    fn pool_audit_goes_stale_when_pool_totals_change() {
+        let mut lock_pool = sealed_pool_at(0);
+        lock_pool.total_locked = 1_000;
+        lock_pool.holder_count = 3;
+        let mut audit = PoolAudit {
+            lock_pool: Pubkey::default(),
+            cursor: 2,
+            user_locked_sum: 700,
+            total_locked: 0,
+            holder_count: 0,
+            unregistered_allocation: 0,
+            bump: 0,
+        };
+
+        let lock_pool_key = Pubkey::new_unique();
+        audit.restart(lock_pool_key, &lock_pool, 253);
+        assert_eq!((audit.lock_pool, audit.cursor, audit.user_locked_sum, audit.bump), (lock_pool_key, 0, 0, 253));
+        assert!(audit.matches(&lock_pool));
+
+        // A claim between slices changes total_locked
+        lock_pool.total_locked -= 10;
+        assert!(!audit.matches(&lock_pool));
+        lock_pool.total_locked += 10;
+
+        // So does a new holder or a proof-based registration
+        lock_pool.holder_count += 1;
+        assert!(!audit.matches(&lock_pool));
+        lock_pool.holder_count -= 1;
+        lock_pool.unregistered_allocation = 5;
+        assert!(!audit.matches(&lock_pool));
+    }
+}
@@ -1,35 +1,35 @@
-use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
-
-#[derive(Accounts)]
-pub struct InitializeLockAccounts<'info> {
-    #[account(mut)]
-    pub lock_pool_account: Account<'info, LockPoolState>, // Track all locking data for users
-    #[account(mut)]
-    pub lock_pool_token_account: Account<'info, TokenAccount>, // Shared lock vault (PDA-owned)
-    #[account(mut)]
-    pub source_wallet: Account<'info, TokenAccount>, // Admin's funding source wallet
-    #[account(mut)]
-    pub admin_wallet: Signer<'info>, // Wallet signing token transfers (Presale Manager)
-    pub token_program: Program<'info, Token>, // Standard SPL Token program
-}
-
-#[derive(Clone, AnchorSerialize, AnchorDeserialize, Debug)]
-pub struct InvestorInfo {
-    pub wallet_address: Pubkey,
-    pub token_amount: u64,
-}
-
-#[account]
-pub struct LockPoolState {
-    pub total_locked: u64,               // Total locked tokens in the pool
-    pub users: Vec<UserLockInfo>,       // List of all users and locked info
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct UserLockInfo {
-    pub user_wallet: Pubkey,            // Wallet address of the user
-    pub total_tokens: u64,              // Purchased tokens during presale
-    pub unlocked_tokens: u64,           // Unlocked tokens (via milestones)
-    pub locked_tokens: u64,             // Remaining locked tokens
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct InitializeLockAccounts<'info> {
+    #[account(mut)]
+    pub lock_pool_account: Account<'info, LockPoolState>, // Track all locking data for users
+    #[account(mut)]
+    pub lock_pool_token_account: Account<'info, TokenAccount>, // Shared lock vault (PDA-owned)
+    #[account(mut)]
+    pub source_wallet: Account<'info, TokenAccount>, // Admin's funding source wallet
+    #[account(mut)]
+    pub admin_wallet: Signer<'info>, // Wallet signing token transfers (Presale Manager)
+    pub token_program: Program<'info, Token>, // Standard SPL Token program
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize, Debug)]
+pub struct InvestorInfo {
+    pub wallet_address: Pubkey,
+    pub token_amount: u64,
+}
+
+#[account]
+pub struct LockPoolState {
+    pub total_locked: u64,               // Total locked tokens in the pool
+    pub users: Vec<UserLockInfo>,       // List of all users and locked info
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct UserLockInfo {
+    pub user_wallet: Pubkey,            // Wallet address of the user
+    pub total_tokens: u64,              // Purchased tokens during presale
+    pub unlocked_tokens: u64,           // Unlocked tokens (via milestones)
+    pub locked_tokens: u64,             // Remaining locked tokens
 }
\ No newline at end of file
@@ -0,0 +1,256 @@
+//! Builds the investor allocation Merkle tree committed with `commit_allocation_root`.
+//!
+//! Usage: `allocation-merkle <allocations.csv>`
+//!
+//! Each CSV line is `wallet,amount,category` (an optional `wallet,amount,category` header is
+//! skipped). Repeated wallets are merged into one leaf with their amounts summed and reported on
+//! stderr. The root, the total to fund and one proof per wallet are printed as JSON; investors
+//! pass their `amount`, `category` and `proof` to `claim_with_proof`.
+
+use std::{env, fs, process, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use hotwings::{allocation_leaf, hash_allocation_pair, InvestorCategory};
+use serde::Serialize;
+
+struct Allocation {
+    wallet: Pubkey,
+    amount: u64,
+    category: InvestorCategory,
+}
+
+#[derive(Serialize)]
+struct AllocationProof {
+    wallet: String,
+    amount: u64,
+    category: String,
+    proof: Vec<String>, // Hex-encoded sibling hashes, leaf to root
+}
+
+#[derive(Serialize)]
+struct AllocationTree {
+    root: String,      // Hex-encoded root for `commit_allocation_root`
+    total_amount: u64, // Tokens to fund with `commit_allocation_root`
+    allocations: Vec<AllocationProof>,
+}
+
+fn main() {
+    let path = env::args().nth(1).unwrap_or_else(|| fail("usage: allocation-merkle <allocations.csv>"));
+    let csv = fs::read_to_string(&path).unwrap_or_else(|e| fail(&format!("cannot read {path}: {e}")));
+    let allocations = parse_csv(&csv).and_then(merge_duplicates).unwrap_or_else(|e| fail(&e));
+    let tree = build_tree(&allocations).unwrap_or_else(|e| fail(&e));
+    println!("{}", serde_json::to_string_pretty(&tree).expect("tree serializes"));
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}");
+    process::exit(1);
+}
+
+fn parse_csv(csv: &str) -> Result<Vec<Allocation>, String> {
+    let allocations = csv
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter(|(i, line)| !(*i == 0 && line.trim_start().starts_with("wallet")))
+        .map(|(i, line)| parse_line(i + 1, line))
+        .collect::<Result<Vec<_>, _>>()?;
+    if allocations.is_empty() {
+        return Err("no allocations in CSV".to_string());
+    }
+    Ok(allocations)
+}
+
+fn parse_line(line_no: usize, line: &str) -> Result<Allocation, String> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    let [wallet, amount, category] = fields[..] else {
+        return Err(format!("line {line_no}: expected wallet,amount,category"));
+    };
+    let wallet = Pubkey::from_str(wallet).map_err(|e| format!("line {line_no}: invalid wallet: {e}"))?;
+    let amount: u64 = amount
+        .parse()
+        .map_err(|e| format!("line {line_no}: invalid amount: {e}"))?;
+    if amount == 0 {
+        return Err(format!("line {line_no}: amount must be greater than 0"));
+    }
+    let category = parse_category(category)
+        .ok_or_else(|| format!("line {line_no}: unknown category {category:?}"))?;
+    Ok(Allocation { wallet, amount, category })
+}
+
+// One leaf per wallet: `claim_with_proof` registers a wallet only once
+fn merge_duplicates(allocations: Vec<Allocation>) -> Result<Vec<Allocation>, String> {
+    let mut merged: Vec<Allocation> = Vec::with_capacity(allocations.len());
+    for allocation in allocations {
+        match merged.iter_mut().find(|m| m.wallet == allocation.wallet) {
+            Some(existing) => {
+                if existing.category != allocation.category {
+                    return Err(format!("wallet {} is listed under several categories", allocation.wallet));
+                }
+                existing.amount = existing
+                    .amount
+                    .checked_add(allocation.amount)
+                    .ok_or_else(|| format!("amount of wallet {} overflows u64", allocation.wallet))?;
+                eprintln!("merged repeated wallet {} (total {})", existing.wallet, existing.amount);
+            }
+            None => merged.push(allocation),
+        }
+    }
+    Ok(merged)
+}
+
+fn parse_category(category: &str) -> Option<InvestorCategory> {
+    match category.to_ascii_lowercase().as_str() {
+        "presale" => Some(InvestorCategory::Presale),
+        "team" => Some(InvestorCategory::Team),
+        "advisors" => Some(InvestorCategory::Advisors),
+        "marketing" => Some(InvestorCategory::Marketing),
+        _ => None,
+    }
+}
+
+fn build_tree(allocations: &[Allocation]) -> Result<AllocationTree, String> {
+    let total_amount = allocations
+        .iter()
+        .try_fold(0u64, |total, a| total.checked_add(a.amount))
+        .ok_or("total amount overflows u64")?;
+
+    let leaves = allocations
+        .iter()
+        .map(|a| allocation_leaf(&a.wallet, a.amount, a.category))
+        .collect();
+    let layers = build_layers(leaves);
+    let root = layers.last().expect("at least one layer")[0];
+
+    let allocations = allocations
+        .iter()
+        .enumerate()
+        .map(|(index, a)| AllocationProof {
+            wallet: a.wallet.to_string(),
+            amount: a.amount,
+            category: format!("{:?}", a.category),
+            proof: proof_for(&layers, index).iter().map(|node| to_hex(node)).collect(),
+        })
+        .collect();
+
+    Ok(AllocationTree {
+        root: to_hex(&root),
+        total_amount,
+        allocations,
+    })
+}
+
+// Layers from the leaves up to the root; an unpaired node is carried up unchanged
+fn build_layers(leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+    let mut layers = vec![leaves];
+    while layers.last().expect("at least one layer").len() > 1 {
+        let next = layers
+            .last()
+            .expect("at least one layer")
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => hash_allocation_pair(a, b),
+                [a] => *a,
+                _ => unreachable!("chunks(2) yields one or two nodes"),
+            })
+            .collect();
+        layers.push(next);
+    }
+    layers
+}
+
+fn proof_for(layers: &[Vec<[u8; 32]>], mut index: usize) -> Vec<[u8; 32]> {
+    let mut proof = Vec::new();
+    for layer in &layers[..layers.len() - 1] {
+        let sibling = index ^ 1;
+        if sibling < layer.len() {
+            proof.push(layer[sibling]);
+        }
+        index /= 2;
+    }
+    proof
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hotwings::verify_allocation_proof;
+
+    fn allocations(count: usize) -> Vec<Allocation> {
+        (0..count)
+            .map(|i| Allocation {
+                wallet: Pubkey::new_unique(),
+                amount: 1_000 * (i as u64 + 1),
+                category: if i % 2 == 0 { InvestorCategory::Presale } else { InvestorCategory::Team },
+            })
+            .collect()
+    }
+
+    fn from_hex(hex: &str) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).expect("hex digit");
+        }
+        bytes
+    }
+
+    #[test]
+    fn every_proof_verifies_against_the_root() {
+        for count in 1..=9 {
+            let allocations = allocations(count);
+            let tree = build_tree(&allocations).unwrap();
+            let root = from_hex(&tree.root);
+
+            assert_eq!(tree.total_amount, allocations.iter().map(|a| a.amount).sum::<u64>());
+            for (allocation, output) in allocations.iter().zip(&tree.allocations) {
+                let proof: Vec<[u8; 32]> = output.proof.iter().map(|node| from_hex(node)).collect();
+                let leaf = allocation_leaf(&allocation.wallet, allocation.amount, allocation.category);
+                assert!(verify_allocation_proof(&proof, &root, leaf), "{count} leaves, wallet {}", output.wallet);
+
+                // The same proof must not verify another amount or category
+                let inflated = allocation_leaf(&allocation.wallet, allocation.amount + 1, allocation.category);
+                assert!(!verify_allocation_proof(&proof, &root, inflated));
+                let recategorized = allocation_leaf(&allocation.wallet, allocation.amount, InvestorCategory::Advisors);
+                assert!(!verify_allocation_proof(&proof, &root, recategorized));
+            }
+        }
+    }
+
+    #[test]
+    fn odd_leaf_is_carried_up_unchanged() {
+        let leaves: Vec<[u8; 32]> = (1..=3u8).map(|i| [i; 32]).collect();
+        let layers = build_layers(leaves.clone());
+
+        assert_eq!(layers.len(), 3);
+        assert_eq!(layers[1], vec![hash_allocation_pair(&leaves[0], &leaves[1]), leaves[2]]);
+        assert_eq!(layers[2], vec![hash_allocation_pair(&layers[1][0], &leaves[2])]);
+
+        // The carried leaf has no sibling on the first layer, so its proof skips it
+        assert_eq!(proof_for(&layers, 2), vec![layers[1][0]]);
+        assert!(verify_allocation_proof(&proof_for(&layers, 2), &layers[2][0], leaves[2]));
+    }
+
+    #[test]
+    fn single_leaf_is_its_own_root() {
+        let layers = build_layers(vec![[7u8; 32]]);
+        assert_eq!(layers, vec![vec![[7u8; 32]]]);
+        assert!(proof_for(&layers, 0).is_empty());
+        assert!(verify_allocation_proof(&[], &[7u8; 32], [7u8; 32]));
+    }
+
+    #[test]
+    fn repeated_wallets_merge_into_one_leaf() {
+        let wallet = Pubkey::new_unique();
+        let csv = format!("wallet,amount,category\n{wallet},100,presale\n{wallet},50,Presale\n");
+        let merged = parse_csv(&csv).and_then(merge_duplicates).unwrap();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].amount, 150);
+
+        let conflicting = format!("{wallet},100,presale\n{wallet},50,team\n");
+        assert!(parse_csv(&conflicting).and_then(merge_duplicates).is_err());
+    }
+}